@@ -0,0 +1,195 @@
+use std::collections::HashMap;
+use std::ffi::OsString;
+
+use crate::errors::{QuomeError, Result};
+
+/// Index of the command word in `args`: the first argument after `args[0]`
+/// that isn't a global option or its value. `valued` lists the options
+/// that take their value as the next argument (`--header`, `-o`, ...).
+pub fn command_index(args: &[OsString], valued: &[String]) -> Option<usize> {
+    let mut i = 1;
+    while let Some(arg) = args.get(i) {
+        match arg.to_str() {
+            Some("--") => return (i + 1 < args.len()).then_some(i + 1),
+            Some(opt) if opt.starts_with('-') && opt.len() > 1 => {
+                i += if valued.iter().any(|v| v == opt) {
+                    2
+                } else {
+                    1
+                };
+            }
+            _ => return Some(i),
+        }
+    }
+    None
+}
+
+/// Replace a user-defined alias in the command position (after any global
+/// options) with its words, repeating while the result starts with another
+/// alias. Built-in commands always win, so an alias can't shadow `logs` or
+/// `apps`.
+pub fn expand(
+    mut args: Vec<OsString>,
+    aliases: &HashMap<String, String>,
+    builtins: &[String],
+    valued: &[String],
+) -> Result<Vec<OsString>> {
+    let mut seen: Vec<String> = Vec::new();
+
+    while let Some(at) = command_index(&args, valued) {
+        let Some(name) = args[at].to_str().map(str::to_owned) else {
+            break;
+        };
+        if builtins.contains(&name) {
+            break;
+        }
+        let Some(expansion) = aliases.get(&name) else {
+            break;
+        };
+        if seen.contains(&name) {
+            seen.push(name);
+            return Err(QuomeError::Alias(format!(
+                "recursive alias: {}",
+                seen.join(" → ")
+            )));
+        }
+
+        let words =
+            split_words(expansion).map_err(|e| QuomeError::Alias(format!("'{}': {}", name, e)))?;
+        if words.is_empty() {
+            return Err(QuomeError::Alias(format!("'{}' is empty", name)));
+        }
+        args.splice(at..at + 1, words.into_iter().map(OsString::from));
+        seen.push(name);
+    }
+
+    Ok(args)
+}
+
+/// Split an alias definition into words, honoring single and double quotes
+/// so `--description "nightly build"` stays one argument.
+fn split_words(line: &str) -> std::result::Result<Vec<String>, &'static str> {
+    let mut words = Vec::new();
+    let mut word = String::new();
+    let mut in_word = false;
+    let mut quote: Option<char> = None;
+
+    for c in line.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), c) => word.push(c),
+            (None, '"' | '\'') => {
+                quote = Some(c);
+                in_word = true;
+            }
+            (None, c) if c.is_whitespace() => {
+                if in_word {
+                    words.push(std::mem::take(&mut word));
+                    in_word = false;
+                }
+            }
+            (None, c) => {
+                word.push(c);
+                in_word = true;
+            }
+        }
+    }
+
+    if quote.is_some() {
+        return Err("unterminated quote");
+    }
+    if in_word {
+        words.push(word);
+    }
+    Ok(words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{command_index, expand, split_words};
+    use std::collections::HashMap;
+    use std::ffi::OsString;
+
+    fn args(words: &[&str]) -> Vec<OsString> {
+        words.iter().map(OsString::from).collect()
+    }
+
+    #[test]
+    fn split_words_honors_quotes() {
+        assert_eq!(
+            split_words(r#"logs --app-name web -n '50'  --x "a b""#).unwrap(),
+            vec!["logs", "--app-name", "web", "-n", "50", "--x", "a b"]
+        );
+        assert_eq!(split_words(r#"x """#).unwrap(), vec!["x", ""]);
+        assert!(split_words("logs 'oops").is_err());
+    }
+
+    #[test]
+    fn expand_substitutes_chains_and_guards_loops() {
+        let builtins = vec!["logs".to_string(), "apps".to_string()];
+        let aliases: HashMap<String, String> = [
+            ("prod-logs", "logs --org-name acme"),
+            ("pl", "prod-logs -n 20"),
+            ("apps", "apps list"),
+            ("a", "b"),
+            ("b", "a"),
+        ]
+        .into_iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+
+        let valued = vec!["--header".to_string()];
+        assert_eq!(
+            expand(
+                args(&["quome", "pl", "--json"]),
+                &aliases,
+                &builtins,
+                &valued
+            )
+            .unwrap(),
+            args(&["quome", "logs", "--org-name", "acme", "-n", "20", "--json"])
+        );
+        // Built-ins are never shadowed
+        assert_eq!(
+            expand(args(&["quome", "apps"]), &aliases, &builtins, &valued).unwrap(),
+            args(&["quome", "apps"])
+        );
+        assert!(expand(args(&["quome", "a"]), &aliases, &builtins, &valued).is_err());
+    }
+
+    #[test]
+    fn expand_looks_past_leading_global_options() {
+        let aliases: HashMap<String, String> = [("pl".to_string(), "logs -n 20".to_string())]
+            .into_iter()
+            .collect();
+        let valued = vec!["--header".to_string(), "-o".to_string()];
+        assert_eq!(
+            expand(
+                args(&["quome", "--no-color", "--header", "X-Debug: 1", "pl"]),
+                &aliases,
+                &[],
+                &valued
+            )
+            .unwrap(),
+            args(&[
+                "quome",
+                "--no-color",
+                "--header",
+                "X-Debug: 1",
+                "logs",
+                "-n",
+                "20"
+            ])
+        );
+
+        assert_eq!(
+            command_index(&args(&["quome", "-o", "json", "apps"]), &valued),
+            Some(3)
+        );
+        assert_eq!(
+            command_index(&args(&["quome", "--output=json", "apps"]), &valued),
+            Some(2)
+        );
+        assert_eq!(command_index(&args(&["quome", "--local"]), &valued), None);
+    }
+}
@@ -1,3 +1,4 @@
+use chrono::{DateTime, SecondsFormat, Utc};
 use uuid::Uuid;
 
 use crate::api::models::*;
@@ -73,15 +74,31 @@ impl QuomeClient {
         .await
     }
 
+    /// The newest `limit` log entries, optionally only those in `[since, until)`.
     pub async fn get_logs(
         &self,
         org_id: Uuid,
         app_id: Uuid,
         limit: Option<u32>,
+        since: Option<DateTime<Utc>>,
+        until: Option<DateTime<Utc>>,
     ) -> Result<AppLogs> {
-        let mut path = format!("/api/v1/orgs/{}/apps/{}/logs", org_id, app_id);
+        let mut query = Vec::new();
         if let Some(l) = limit {
-            path = format!("{}?limit={}", path, l);
+            query.push(format!("limit={}", l));
+        }
+        // `Z` rather than `+00:00`, which would need escaping in a query
+        let stamp = |t: DateTime<Utc>| t.to_rfc3339_opts(SecondsFormat::Secs, true);
+        if let Some(t) = since {
+            query.push(format!("since={}", stamp(t)));
+        }
+        if let Some(t) = until {
+            query.push(format!("until={}", stamp(t)));
+        }
+
+        let mut path = format!("/api/v1/orgs/{}/apps/{}/logs", org_id, app_id);
+        if !query.is_empty() {
+            path = format!("{}?{}", path, query.join("&"));
         }
         self.get(&path).await
     }
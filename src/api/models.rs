@@ -1,6 +1,6 @@
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use uuid::Uuid;
 
 // ============ Common ============
@@ -43,6 +43,10 @@ pub struct User {
     pub email_verified: bool,
     #[serde(default)]
     pub default_org_id: Option<Uuid>,
+    /// Whether two-factor authentication is on. Not part of the documented
+    /// user object; only shown when a server sends it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub two_factor: Option<bool>,
     pub created_at: DateTime<Utc>,
     pub updated_at: DateTime<Utc>,
 }
@@ -183,6 +187,75 @@ pub struct App {
     pub updated_at: DateTime<Utc>,
 }
 
+/// One entry of `spec.containers`, as `apps get --containers` reports it.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct ContainerSpec {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    pub image: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+}
+
+impl App {
+    /// Where the app is served: its primary URL, else the custom domain,
+    /// else the platform-assigned service URL. `None` until the first
+    /// deployment has gone live.
+    pub fn url(&self) -> Option<String> {
+        self.primary_url
+            .clone()
+            .or_else(|| {
+                self.custom_domain
+                    .as_ref()
+                    .map(|d| format!("https://{}", d))
+            })
+            .or_else(|| self.cloud_run_url.clone())
+    }
+
+    /// Plain environment variables from `spec.env_vars`, by name. Secrets
+    /// aren't part of the spec; they're injected at deploy time.
+    pub fn env_vars(&self) -> BTreeMap<String, String> {
+        let vars = self
+            .spec
+            .as_ref()
+            .and_then(|s| s.get("env_vars"))
+            .and_then(|v| v.as_object());
+        vars.into_iter()
+            .flatten()
+            .map(|(name, value)| {
+                let value = match value {
+                    serde_json::Value::String(s) => s.clone(),
+                    other => other.to_string(),
+                };
+                (name.clone(), value)
+            })
+            .collect()
+    }
+
+    /// The app's containers. Single-image apps have a flat spec with no
+    /// `containers` list, so they're reported as one container built from
+    /// the image and `spec.port`.
+    pub fn containers(&self) -> Vec<ContainerSpec> {
+        let spec = self.spec.as_ref();
+        if let Some(list) = spec.and_then(|s| s.get("containers")) {
+            if let Ok(containers) = serde_json::from_value(list.clone()) {
+                return containers;
+            }
+        }
+        match &self.container_image_url {
+            Some(image) => vec![ContainerSpec {
+                name: Some(self.name.clone()),
+                image: image.clone(),
+                port: spec
+                    .and_then(|s| s.get("port"))
+                    .and_then(|p| p.as_u64())
+                    .and_then(|p| u16::try_from(p).ok()),
+            }],
+            None => Vec::new(),
+        }
+    }
+}
+
 /// `source` discriminated union — only the variants the CLI can construct.
 #[derive(Debug, Serialize)]
 #[serde(tag = "type")]
@@ -257,6 +330,16 @@ pub enum DeploymentStatus {
     Cancelled,
 }
 
+impl DeploymentStatus {
+    /// Whether the deployment has stopped changing.
+    pub fn is_terminal(&self) -> bool {
+        matches!(
+            self,
+            DeploymentStatus::Success | DeploymentStatus::Failed | DeploymentStatus::Cancelled
+        )
+    }
+}
+
 impl std::fmt::Display for DeploymentStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -276,7 +359,8 @@ pub struct DeploymentEvent {
     pub created_at: DateTime<Utc>,
     pub message: String,
     #[serde(default)]
-    pub details: Option<HashMap<String, serde_json::Value>>,
+    /// Sorted so `--events-json` output is stable across runs
+    pub details: Option<BTreeMap<String, serde_json::Value>>,
 }
 
 #[derive(Debug, Serialize, Default)]
@@ -347,7 +431,6 @@ pub struct AuditLog {
 pub struct AuditLogList {
     pub items: Vec<AuditLog>,
     #[serde(default)]
-    #[allow(dead_code)]
     pub total: Option<i64>,
 }
 
@@ -418,3 +501,74 @@ pub struct UpdateDatabaseRequest {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub ha_enabled: Option<bool>,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{App, ContainerSpec};
+    use serde_json::json;
+
+    fn app(image: Option<&str>, spec: serde_json::Value) -> App {
+        serde_json::from_value(json!({
+            "id": "7c9e6679-0000-4000-8000-000000000001",
+            "name": "web",
+            "organization_id": "0d9f4a3b-0000-4000-8000-000000000001",
+            "status": "running",
+            "container_image_url": image,
+            "spec": spec,
+            "created_at": "2026-07-01T00:00:00Z",
+            "updated_at": "2026-07-01T00:00:00Z",
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn url_falls_back_from_primary_to_domain_to_service_url() {
+        let mut app = app(None, json!({}));
+        assert_eq!(app.url(), None);
+        app.cloud_run_url = Some("https://web-abc.run.app".into());
+        assert_eq!(app.url().unwrap(), "https://web-abc.run.app");
+        app.custom_domain = Some("web.acme.com".into());
+        assert_eq!(app.url().unwrap(), "https://web.acme.com");
+        app.primary_url = Some("https://web-acme.q.run".into());
+        assert_eq!(app.url().unwrap(), "https://web-acme.q.run");
+    }
+
+    #[test]
+    fn containers_prefers_spec_list_then_falls_back_to_image() {
+        let listed = app(
+            Some("ignored:1"),
+            json!({"containers": [{"name": "api", "image": "acme/api:2", "port": 8080}]}),
+        );
+        assert_eq!(
+            listed.containers(),
+            vec![ContainerSpec {
+                name: Some("api".into()),
+                image: "acme/api:2".into(),
+                port: Some(8080),
+            }]
+        );
+
+        let flat = app(Some("nginx:1.27"), json!({"port": 80}));
+        assert_eq!(flat.containers()[0].image, "nginx:1.27");
+        assert_eq!(flat.containers()[0].port, Some(80));
+
+        assert!(app(None, json!({})).containers().is_empty());
+    }
+
+    #[test]
+    fn env_vars_reads_the_spec_map() {
+        let with_vars = app(
+            None,
+            json!({"env_vars": {"RUST_LOG": "info", "WORKERS": 4}}),
+        );
+        let vars: Vec<(String, String)> = with_vars.env_vars().into_iter().collect();
+        assert_eq!(
+            vars,
+            [
+                ("RUST_LOG".to_string(), "info".to_string()),
+                ("WORKERS".to_string(), "4".to_string()),
+            ]
+        );
+        assert!(app(None, json!({})).env_vars().is_empty());
+    }
+}
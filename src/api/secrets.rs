@@ -3,6 +3,7 @@ use uuid::Uuid;
 use crate::api::models::*;
 use crate::client::QuomeClient;
 use crate::errors::Result;
+use crate::util;
 
 impl QuomeClient {
     pub async fn list_secrets(&self, org_id: Uuid) -> Result<PaginatedResponse<Secret>> {
@@ -11,17 +12,21 @@ impl QuomeClient {
     }
 
     pub async fn create_secret(&self, org_id: Uuid, req: &CreateSecretRequest) -> Result<Secret> {
+        util::register_sensitive(&req.value);
         self.post(&format!("/api/v1/orgs/{}/secrets", org_id), req)
             .await
     }
 
     /// Fetch the decrypted value of a secret by name.
     pub async fn get_secret_value(&self, org_id: Uuid, name: &str) -> Result<SecretValue> {
-        self.get(&format!(
-            "/api/v1/orgs/{}/secrets/by-name/{}/value",
-            org_id, name
-        ))
-        .await
+        let secret: SecretValue = self
+            .get(&format!(
+                "/api/v1/orgs/{}/secrets/by-name/{}/value",
+                org_id, name
+            ))
+            .await?;
+        util::register_sensitive(&secret.value);
+        Ok(secret)
     }
 
     pub async fn update_secret(
@@ -30,6 +35,9 @@ impl QuomeClient {
         secret_id: Uuid,
         req: &UpdateSecretRequest,
     ) -> Result<Secret> {
+        if let Some(ref value) = req.value {
+            util::register_sensitive(value);
+        }
         self.put(
             &format!("/api/v1/orgs/{}/secrets/{}", org_id, secret_id),
             req,
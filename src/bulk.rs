@@ -0,0 +1,254 @@
+use colored::Colorize;
+use futures::stream::{self, StreamExt};
+use serde::Serialize;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, Ordering};
+use tabled::Tabled;
+
+use crate::errors::{QuomeError, Result};
+use crate::ui;
+
+/// Maximum number of requests in flight during a bulk operation.
+const CONCURRENCY: usize = 4;
+
+/// Narrow `items` to those whose name contains `filter`. Without a filter,
+/// refuse unless `really_all` is set so a bare `--all` can't wipe an org.
+pub fn select<T>(
+    items: Vec<T>,
+    name: impl Fn(&T) -> &str,
+    filter: Option<&str>,
+    really_all: bool,
+    kind: &str,
+) -> Result<Vec<T>> {
+    match filter {
+        Some(f) => Ok(items.into_iter().filter(|i| name(i).contains(f)).collect()),
+        None if really_all => Ok(items),
+        None => Err(QuomeError::ApiError(format!(
+            "Refusing to select every {} without --filter. Pass --really-all to confirm.",
+            kind
+        ))),
+    }
+}
+
+/// Show exactly which resources will be affected and ask once. The list
+/// goes to stderr so `--json` output stays clean.
+pub fn confirm(action: &str, kind: &str, names: &[String], force: bool) -> Result<bool> {
+    eprintln!("{} {} {}:", action, names.len(), kind);
+    for name in names {
+        eprintln!("  {} {}", "•".cyan(), name);
+    }
+    eprintln!();
+
+    if force {
+        return Ok(true);
+    }
+
+    inquire::Confirm::new(&format!("{} these {} {}?", action, names.len(), kind))
+        .with_default(false)
+        .prompt()
+        .map_err(|e| QuomeError::Io(std::io::Error::other(e.to_string())))
+}
+
+/// Run `f` over `items` with bounded parallelism, returning results in input order.
+pub async fn run<T, R, F, Fut>(items: Vec<T>, f: F) -> Vec<Result<R>>
+where
+    F: Fn(T) -> Fut,
+    Fut: Future<Output = Result<R>>,
+{
+    stream::iter(items)
+        .map(f)
+        .buffered(CONCURRENCY)
+        .collect()
+        .await
+}
+
+/// What happened to one item of a batch.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Outcome {
+    Succeeded,
+    Failed,
+    Skipped,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BatchItem {
+    pub name: String,
+    pub outcome: Outcome,
+    /// Why it failed or was skipped
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
+/// Summary of a batch command (bulk delete, invite from file, ...), shared so
+/// every batch reports, serializes and exits the same way.
+#[derive(Debug, Serialize)]
+pub struct BatchResult {
+    pub action: String,
+    pub succeeded: usize,
+    pub failed: usize,
+    pub skipped: usize,
+    pub items: Vec<BatchItem>,
+}
+
+#[derive(Tabled)]
+struct BatchRow {
+    #[tabled(rename = "NAME")]
+    name: String,
+    #[tabled(rename = "RESULT")]
+    outcome: String,
+    #[tabled(rename = "REASON")]
+    reason: String,
+}
+
+impl BatchResult {
+    pub fn new(action: &str) -> Self {
+        Self {
+            action: action.to_string(),
+            succeeded: 0,
+            failed: 0,
+            skipped: 0,
+            items: Vec::new(),
+        }
+    }
+
+    pub fn push(&mut self, name: String, outcome: Outcome, reason: Option<String>) {
+        match outcome {
+            Outcome::Succeeded => self.succeeded += 1,
+            Outcome::Failed => self.failed += 1,
+            Outcome::Skipped => self.skipped += 1,
+        }
+        self.items.push(BatchItem {
+            name,
+            outcome,
+            reason,
+        });
+    }
+
+    pub fn skip(&mut self, name: String, reason: &str) {
+        self.push(name, Outcome::Skipped, Some(reason.to_string()));
+    }
+
+    /// Print the per-item table and a one-line summary (or the whole result
+    /// as JSON or YAML). Fails if any item failed, so scripts see a nonzero exit.
+    pub fn report(&self, json: bool) -> Result<()> {
+        if json {
+            ui::print_structured(self)?;
+        } else {
+            let rows = self.items.iter().map(|item| BatchRow {
+                name: item.name.clone(),
+                outcome: match item.outcome {
+                    Outcome::Succeeded => "✓ ok".green().to_string(),
+                    Outcome::Failed => "✗ failed".red().to_string(),
+                    Outcome::Skipped => "- skipped".dimmed().to_string(),
+                },
+                reason: item.reason.clone().unwrap_or_default(),
+            });
+            ui::print_table(rows.collect());
+
+            let mut summary = format!("{} {} of {}", self.action, self.succeeded, self.items.len());
+            if self.failed > 0 {
+                summary.push_str(&format!(", {} failed", self.failed));
+            }
+            if self.skipped > 0 {
+                summary.push_str(&format!(", {} skipped", self.skipped));
+            }
+            println!("{}", summary);
+        }
+
+        if self.failed > 0 {
+            return Err(QuomeError::ApiError(format!(
+                "{} of {} operations failed",
+                self.failed,
+                self.items.len()
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// [`run`] for a batch command: each item is a `(name, input)` pair and the
+/// outcomes are collected into a [`BatchResult`]. With `fail_fast`, items
+/// that haven't started when one fails are skipped instead of attempted.
+pub async fn run_batch<T, R, F, Fut>(
+    action: &str,
+    items: Vec<(String, T)>,
+    fail_fast: bool,
+    f: F,
+) -> BatchResult
+where
+    F: Fn(T) -> Fut,
+    Fut: Future<Output = Result<R>>,
+{
+    let stop = AtomicBool::new(false);
+    let (names, inputs): (Vec<String>, Vec<T>) = items.into_iter().unzip();
+    let outcomes = run(inputs, |input| {
+        let stop = &stop;
+        let call = &f;
+        async move {
+            if stop.load(Ordering::SeqCst) {
+                return Ok(None);
+            }
+            let result = call(input).await;
+            if result.is_err() && fail_fast {
+                stop.store(true, Ordering::SeqCst);
+            }
+            result.map(Some)
+        }
+    })
+    .await;
+
+    let mut batch = BatchResult::new(action);
+    for (name, outcome) in names.into_iter().zip(outcomes) {
+        match outcome {
+            Ok(Some(_)) => batch.push(name, Outcome::Succeeded, None),
+            Ok(None) => batch.skip(name, "not attempted after an earlier failure"),
+            Err(e) => batch.push(name, Outcome::Failed, Some(e.to_string())),
+        }
+    }
+    batch
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{run_batch, select, Outcome};
+    use crate::errors::QuomeError;
+
+    #[test]
+    fn select_requires_filter_or_really_all() {
+        let names = vec!["pr-1", "pr-2", "prod"];
+        let picked = select(names.clone(), |n| n, Some("pr-"), false, "app").unwrap();
+        assert_eq!(picked, vec!["pr-1", "pr-2"]);
+        assert!(select(names.clone(), |n| n, None, false, "app").is_err());
+        assert_eq!(select(names, |n| n, None, true, "app").unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn run_batch_counts_outcomes_and_stops_on_fail_fast() {
+        let items = |n: u32| {
+            (1..=n)
+                .map(|i| (format!("item-{}", i), i))
+                .collect::<Vec<_>>()
+        };
+        let fail_on_two = |i: u32| async move {
+            if i == 2 {
+                Err(QuomeError::ApiError("boom".into()))
+            } else {
+                Ok(i)
+            }
+        };
+
+        let all = run_batch("Deleted", items(3), false, fail_on_two).await;
+        assert_eq!((all.succeeded, all.failed, all.skipped), (2, 1, 0));
+        assert_eq!(all.items[1].outcome, Outcome::Failed);
+        assert_eq!(all.items[1].reason.as_deref(), Some("API error: boom"));
+
+        // Concurrency is 4, so only items queued after the first four are
+        // guaranteed to be skipped once item 2 fails
+        let fast = run_batch("Deleted", items(12), true, fail_on_two).await;
+        assert_eq!(fast.failed, 1);
+        assert!(fast.skipped >= 8);
+        assert_eq!(fast.items[11].outcome, Outcome::Skipped);
+        assert!(fast.report(true).is_err());
+    }
+}
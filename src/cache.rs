@@ -0,0 +1,142 @@
+use colored::Colorize;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use std::fs;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use crate::config::Config;
+use crate::errors::{QuomeError, Result};
+
+const CACHE_DIR: &str = "cache";
+
+/// Subdirectory holding the last result of each read command, for `--offline`.
+const OFFLINE_DIR: &str = "offline";
+
+/// Entry count and total size of the on-disk cache.
+#[derive(Debug, Default, Serialize)]
+pub struct Stats {
+    pub entries: u64,
+    pub bytes: u64,
+}
+
+/// `~/.quome/cache/` — everything under here is safe to delete at any time.
+pub fn dir() -> Result<PathBuf> {
+    Ok(Config::config_dir()?.join(CACHE_DIR))
+}
+
+pub fn stats() -> Result<Stats> {
+    let mut stats = Stats::default();
+    let dir = dir()?;
+    if dir.exists() {
+        walk(&dir, &mut stats)?;
+    }
+    Ok(stats)
+}
+
+/// Remove the whole cache directory, returning what was removed.
+pub fn clear() -> Result<Stats> {
+    let stats = stats()?;
+    let dir = dir()?;
+    if dir.exists() {
+        fs::remove_dir_all(&dir)?;
+    }
+    Ok(stats)
+}
+
+/// Read the entry `name` if it was written less than `max_age` ago.
+pub fn read<T: DeserializeOwned>(name: &str, max_age: Duration) -> Option<T> {
+    let (value, age) = read_with_age(name)?;
+    (age <= max_age).then_some(value)
+}
+
+/// Read the entry `name` whatever its age, along with that age.
+fn read_with_age<T: DeserializeOwned>(name: &str) -> Option<(T, Duration)> {
+    let path = dir().ok()?.join(name);
+    let age = fs::metadata(&path).ok()?.modified().ok()?.elapsed().ok()?;
+    let value = serde_json::from_slice(&fs::read(&path).ok()?).ok()?;
+    Some((value, age))
+}
+
+/// Store `value` as the entry `name`. Best effort: a cache that can't be
+/// written just means the next call fetches again.
+pub fn write<T: Serialize>(name: &str, value: &T) {
+    let Ok(dir) = dir() else { return };
+    let path = dir.join(name);
+    if let (Some(parent), Ok(bytes)) = (path.parent(), serde_json::to_vec(value)) {
+        let _ = fs::create_dir_all(parent).and_then(|_| fs::write(&path, bytes));
+    }
+}
+
+/// Run `fetch` and remember its result as `offline/<key>.json`. With
+/// `offline` the network is skipped and the remembered result is returned
+/// instead, with a warning on stderr saying how old it is.
+pub async fn through<T, F>(key: &str, offline: bool, fetch: F) -> Result<T>
+where
+    T: Serialize + DeserializeOwned,
+    F: Future<Output = Result<T>>,
+{
+    let name = format!("{}/{}.json", OFFLINE_DIR, key);
+    if offline {
+        let (value, age) = read_with_age(&name).ok_or_else(|| {
+            QuomeError::NotFound(
+                "nothing cached for this command yet; run it once without --offline".into(),
+            )
+        })?;
+        eprintln!(
+            "{} Offline: showing cached data from {} ago",
+            "⚠".yellow(),
+            format_age(age)
+        );
+        return Ok(value);
+    }
+
+    let value = fetch.await?;
+    write(&name, &value);
+    Ok(value)
+}
+
+/// The result [`through`] last remembered under `key`, whatever its age.
+pub fn offline<T: DeserializeOwned>(key: &str) -> Option<T> {
+    read_with_age(&format!("{}/{}.json", OFFLINE_DIR, key)).map(|(value, _)| value)
+}
+
+/// Coarse human age: `42s`, `5m`, `3h`, `2d`.
+pub(crate) fn format_age(age: Duration) -> String {
+    let secs = age.as_secs();
+    match secs {
+        0..=59 => format!("{}s", secs),
+        60..=3599 => format!("{}m", secs / 60),
+        3600..=86399 => format!("{}h", secs / 3600),
+        _ => format!("{}d", secs / 86400),
+    }
+}
+
+fn walk(dir: &Path, stats: &mut Stats) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let meta = entry.metadata()?;
+        if meta.is_dir() {
+            walk(&entry.path(), stats)?;
+        } else {
+            stats.entries += 1;
+            stats.bytes += meta.len();
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_age;
+    use std::time::Duration;
+
+    #[test]
+    fn format_age_uses_largest_unit() {
+        assert_eq!(format_age(Duration::from_secs(42)), "42s");
+        assert_eq!(format_age(Duration::from_secs(59 * 60 + 59)), "59m");
+        assert_eq!(format_age(Duration::from_secs(3 * 3600 + 5)), "3h");
+        assert_eq!(format_age(Duration::from_secs(2 * 86400)), "2d");
+    }
+}
@@ -1,17 +1,150 @@
-use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
+use chrono::{DateTime, Utc};
+use colored::Colorize;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, CONTENT_TYPE, DATE};
 use reqwest::StatusCode;
 use serde::de::DeserializeOwned;
 use serde::Serialize;
-use std::time::Duration;
+use std::fs;
+use std::path::Path;
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
 
 use crate::errors::{QuomeError, Result};
-use crate::settings::Settings;
+use crate::settings::{RetrySettings, Settings};
+use crate::util;
 
 const USER_AGENT: &str = concat!("quome-cli/", env!("CARGO_PKG_VERSION"));
 
+/// Headers from `quome --header`, added to every request this process makes.
+static EXTRA_HEADERS: OnceLock<Vec<(HeaderName, HeaderValue)>> = OnceLock::new();
+
+/// Server clock minus local clock, from the `Date` header of the first
+/// response this process gets.
+static CLOCK_SKEW: OnceLock<chrono::Duration> = OnceLock::new();
+
+/// Skew beyond which `whoami` warns and [`server_now`] corrects the clock.
+/// `Date` only has whole seconds and includes the request's latency, so
+/// smaller differences are noise.
+pub const SKEW_TOLERANCE: chrono::Duration = chrono::Duration::seconds(30);
+
+/// How far the local clock is behind (positive) or ahead of the API's, once
+/// a response has been seen.
+pub fn clock_skew() -> Option<chrono::Duration> {
+    CLOCK_SKEW.get().copied()
+}
+
+/// The current time by the API's clock, for comparing against server-issued
+/// expiry times. Falls back to the local clock before any response, or when
+/// the two agree within [`SKEW_TOLERANCE`].
+pub fn server_now() -> DateTime<Utc> {
+    let now = Utc::now();
+    match clock_skew() {
+        Some(skew) if skew.abs() > SKEW_TOLERANCE => now + skew,
+        _ => now,
+    }
+}
+
+fn record_skew(response: &reqwest::Response) {
+    if CLOCK_SKEW.get().is_some() {
+        return;
+    }
+    let server = response
+        .headers()
+        .get(DATE)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| DateTime::parse_from_rfc2822(v).ok());
+    if let Some(server) = server {
+        let _ = CLOCK_SKEW.set(server.with_timezone(&Utc) - Utc::now());
+    }
+}
+
+/// One HTTP exchange, for `--trace-timing`.
+struct Timing {
+    method: String,
+    path: String,
+    /// `None` when the request failed without a response
+    status: Option<u16>,
+    elapsed: Duration,
+}
+
+/// Every request this process made, once `--trace-timing` turns it on.
+static TIMINGS: OnceLock<Mutex<Vec<Timing>>> = OnceLock::new();
+
+/// Start recording request timings for [`report_timings`].
+pub fn enable_trace_timing() {
+    let _ = TIMINGS.set(Mutex::new(Vec::new()));
+}
+
+/// Print the recorded timings to stderr, if `--trace-timing` is on.
+/// `elapsed` is the whole command's wall-clock time.
+pub fn report_timings(elapsed: Duration) {
+    let Some(timings) = TIMINGS.get() else {
+        return;
+    };
+    let timings = timings.lock().unwrap_or_else(|e| e.into_inner());
+    eprint!("{}", timing_report(&timings, elapsed));
+}
+
+fn timing_report(timings: &[Timing], elapsed: Duration) -> String {
+    let width = timings.iter().map(|t| t.path.len()).max().unwrap_or(0);
+    let mut out = String::new();
+    for t in timings {
+        let status = t.status.map_or("---".to_string(), |s| s.to_string());
+        out.push_str(&format!(
+            "{:<6} {:<width$}  {}  {:>6} ms\n",
+            t.method,
+            t.path,
+            status,
+            t.elapsed.as_millis(),
+        ));
+    }
+    let in_requests: Duration = timings.iter().map(|t| t.elapsed).sum();
+    out.push_str(&format!(
+        "{} request{} took {} ms of {} ms total\n",
+        timings.len(),
+        if timings.len() == 1 { "" } else { "s" },
+        in_requests.as_millis(),
+        elapsed.as_millis(),
+    ));
+    out
+}
+
+/// Parse and remember the `--header` values. Replacing the credentials the
+/// CLI sends is allowed, but never silently.
+pub fn set_extra_headers(raw: &[String]) -> Result<()> {
+    let headers = raw
+        .iter()
+        .map(|h| parse_header(h))
+        .collect::<Result<Vec<_>>>()?;
+    for (name, value) in &headers {
+        if *name == AUTHORIZATION || name == "x-api-key" {
+            if let Ok(value) = value.to_str() {
+                util::register_sensitive(value);
+            }
+            eprintln!(
+                "{} --header {} replaces the credentials quome would send",
+                "warning:".yellow().bold(),
+                name
+            );
+        }
+    }
+    let _ = EXTRA_HEADERS.set(headers);
+    Ok(())
+}
+
+/// `"Name: value"` → a validated header pair.
+fn parse_header(raw: &str) -> Result<(HeaderName, HeaderValue)> {
+    let invalid = || QuomeError::InvalidHeader(format!("'{}'", raw));
+    let (name, value) = raw.split_once(':').ok_or_else(invalid)?;
+    let name = HeaderName::from_bytes(name.trim().as_bytes()).map_err(|_| invalid())?;
+    let value = HeaderValue::from_str(value.trim()).map_err(|_| invalid())?;
+    Ok((name, value))
+}
+
 pub struct QuomeClient {
     http: reqwest::Client,
     base_url: String,
+    retry: RetrySettings,
 }
 
 /// FastAPI error bodies are `{"detail": "..."}` where detail may also be a
@@ -28,63 +161,191 @@ fn extract_detail(text: &str) -> Option<String> {
     }
 }
 
+/// The error for a failed response. The API can echo request values (a
+/// rejected secret, say) back in `detail`, so it's redacted before anyone sees it.
+fn error_for(status: StatusCode, text: &str) -> QuomeError {
+    let detail = || extract_detail(text).map(|d| util::redact(&d));
+    match status {
+        StatusCode::UNAUTHORIZED => QuomeError::Unauthorized,
+        StatusCode::NOT_FOUND => {
+            QuomeError::NotFound(detail().unwrap_or_else(|| "Resource not found".into()))
+        }
+        StatusCode::FORBIDDEN => QuomeError::Forbidden(
+            detail().unwrap_or_else(|| format!("Request failed with status {}", status)),
+        ),
+        StatusCode::TOO_MANY_REQUESTS => QuomeError::RateLimited,
+        _ => QuomeError::ApiError(
+            detail().unwrap_or_else(|| format!("Request failed with status {}", status)),
+        ),
+    }
+}
+
+/// Statuses worth another attempt: rate limiting and gateway hiccups.
+fn is_transient(status: StatusCode) -> bool {
+    matches!(
+        status,
+        StatusCode::TOO_MANY_REQUESTS
+            | StatusCode::BAD_GATEWAY
+            | StatusCode::SERVICE_UNAVAILABLE
+            | StatusCode::GATEWAY_TIMEOUT
+    )
+}
+
+/// Read a PEM file, naming it in the error so a bad path is easy to spot.
+fn read_pem(path: &Path, what: &str) -> Result<Vec<u8>> {
+    fs::read(path).map_err(|e| {
+        QuomeError::Io(std::io::Error::new(
+            e.kind(),
+            format!("{} {}: {}", what, path.display(), e),
+        ))
+    })
+}
+
 impl QuomeClient {
     pub fn new(token: Option<&str>, base_url: Option<&str>) -> Result<Self> {
         let mut headers = HeaderMap::new();
         headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
 
         if let Some(t) = token {
+            util::register_sensitive(t);
             let mut key_value =
                 HeaderValue::from_str(t).map_err(|_| QuomeError::InvalidResponse)?;
             key_value.set_sensitive(true);
             headers.insert("X-API-Key", key_value);
         }
 
-        let http = reqwest::Client::builder()
-            .user_agent(USER_AGENT)
-            .default_headers(headers)
-            .timeout(Duration::from_secs(30))
-            .build()?;
+        for (name, value) in EXTRA_HEADERS.get().into_iter().flatten() {
+            let mut value = value.clone();
+            value.set_sensitive(*name == AUTHORIZATION || name == "x-api-key");
+            headers.insert(name.clone(), value);
+        }
 
         // Load settings and determine base URL
         let settings = Settings::load().unwrap_or_default();
+
+        let mut builder = reqwest::Client::builder()
+            .user_agent(USER_AGENT)
+            .default_headers(headers)
+            .timeout(Duration::from_secs(30));
+
+        if let Some(path) = settings.get_ca_cert_path() {
+            let pem = read_pem(&path, "CA certificate")?;
+            builder = builder.add_root_certificate(reqwest::Certificate::from_pem(&pem)?);
+        }
+
+        // Mutual TLS: reqwest wants the certificate chain and key in one PEM buffer
+        match (
+            settings.get_client_cert_path(),
+            settings.get_client_key_path(),
+        ) {
+            (Some(cert), key) => {
+                let mut pem = read_pem(&cert, "client certificate")?;
+                if let Some(key) = key {
+                    pem.push(b'\n');
+                    pem.extend(read_pem(&key, "client key")?);
+                }
+                builder = builder.identity(reqwest::Identity::from_pem(&pem)?);
+            }
+            (None, Some(_)) => {
+                return Err(QuomeError::Io(std::io::Error::new(
+                    std::io::ErrorKind::InvalidInput,
+                    "a client key is configured without a client certificate",
+                )))
+            }
+            (None, None) => {}
+        }
+
+        let http = builder.build()?;
+
         let base_url = base_url
             .map(String::from)
             .unwrap_or_else(|| settings.get_api_url());
 
-        Ok(Self { http, base_url })
+        Ok(Self {
+            http,
+            base_url,
+            retry: settings.retry,
+        })
     }
 
     fn url(&self, path: &str) -> String {
         format!("{}{}", self.base_url, path)
     }
 
-    async fn error_from_response(&self, response: reqwest::Response) -> QuomeError {
-        let status = response.status();
-        match status {
-            StatusCode::UNAUTHORIZED => QuomeError::Unauthorized,
-            StatusCode::NOT_FOUND => {
-                let text = response.text().await.unwrap_or_default();
-                QuomeError::NotFound(
-                    extract_detail(&text).unwrap_or_else(|| "Resource not found".into()),
-                )
-            }
-            StatusCode::TOO_MANY_REQUESTS => QuomeError::RateLimited,
-            _ => {
-                let text = response.text().await.unwrap_or_default();
-                QuomeError::ApiError(
-                    extract_detail(&text)
-                        .unwrap_or_else(|| format!("Request failed with status {}", status)),
-                )
+    /// Send `request`, retrying transient failures as configured in
+    /// `settings.json`. Whatever the last attempt returned is passed on.
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<reqwest::Response> {
+        let request = request.build()?;
+        let retries = self.retry.retries_for(request.method().as_str());
+
+        let mut attempt = 0;
+        loop {
+            let Some(this) = request.try_clone().filter(|_| attempt < retries) else {
+                let response = self.execute(request).await?;
+                record_skew(&response);
+                return Ok(response);
+            };
+            let transient = match self.execute(this).await {
+                Ok(response) if !is_transient(response.status()) => {
+                    record_skew(&response);
+                    return Ok(response);
+                }
+                Ok(response) => response.status().to_string(),
+                Err(e) if e.is_connect() || e.is_timeout() => e.to_string(),
+                Err(e) => return Err(e.into()),
+            };
+
+            let wait = self.retry.backoff(attempt);
+            if std::env::var("QUOME_DEBUG").is_ok() {
+                eprintln!(
+                    "DEBUG retry {}/{} for {} {} in {}ms: {}",
+                    attempt + 1,
+                    retries,
+                    request.method(),
+                    request.url(),
+                    wait.as_millis(),
+                    util::redact(&transient)
+                );
             }
+            tokio::time::sleep(wait).await;
+            attempt += 1;
         }
     }
 
+    /// One attempt at `request`, timed for `--trace-timing`. The time runs
+    /// until the response headers arrive; reading the body isn't included.
+    async fn execute(&self, request: reqwest::Request) -> reqwest::Result<reqwest::Response> {
+        let Some(timings) = TIMINGS.get() else {
+            return self.http.execute(request).await;
+        };
+        let method = request.method().to_string();
+        let path = request.url().path().to_string();
+        let started = Instant::now();
+        let result = self.http.execute(request).await;
+        let timing = Timing {
+            method,
+            path,
+            status: result.as_ref().ok().map(|r| r.status().as_u16()),
+            elapsed: started.elapsed(),
+        };
+        timings
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(timing);
+        result
+    }
+
+    async fn error_from_response(&self, response: reqwest::Response) -> QuomeError {
+        let status = response.status();
+        let text = response.text().await.unwrap_or_default();
+        error_for(status, &text)
+    }
+
     async fn handle_response<T: DeserializeOwned>(&self, response: reqwest::Response) -> Result<T> {
         if response.status().is_success() {
             let text = response.text().await?;
             if std::env::var("QUOME_DEBUG").is_ok() {
-                eprintln!("DEBUG response: {}", text);
+                eprintln!("DEBUG response: {}", util::redact(&text));
             }
             let body: T = serde_json::from_str(&text)?;
             Ok(body)
@@ -102,22 +363,77 @@ impl QuomeClient {
     }
 
     pub async fn get<T: DeserializeOwned>(&self, path: &str) -> Result<T> {
-        let response = self.http.get(self.url(path)).send().await?;
+        let response = self.send(self.http.get(self.url(path))).await?;
         self.handle_response(response).await
     }
 
     pub async fn post<T: DeserializeOwned, B: Serialize>(&self, path: &str, body: &B) -> Result<T> {
-        let response = self.http.post(self.url(path)).json(body).send().await?;
+        let response = self.send(self.http.post(self.url(path)).json(body)).await?;
         self.handle_response(response).await
     }
 
     pub async fn put<T: DeserializeOwned, B: Serialize>(&self, path: &str, body: &B) -> Result<T> {
-        let response = self.http.put(self.url(path)).json(body).send().await?;
+        let response = self.send(self.http.put(self.url(path)).json(body)).await?;
         self.handle_response(response).await
     }
 
     pub async fn delete(&self, path: &str) -> Result<()> {
-        let response = self.http.delete(self.url(path)).send().await?;
+        let response = self.send(self.http.delete(self.url(path))).await?;
         self.handle_empty_response(response).await
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{error_for, parse_header, timing_report, Timing};
+    use crate::util::register_sensitive;
+    use reqwest::StatusCode;
+    use std::time::Duration;
+
+    #[test]
+    fn parse_header_validates_name_and_value() {
+        let (name, value) = parse_header("X-Debug:  1 ").unwrap();
+        assert_eq!(name, "x-debug");
+        assert_eq!(value, "1");
+        assert!(parse_header("X-Debug 1").is_err());
+        assert!(parse_header("Bad Name: 1").is_err());
+        assert!(parse_header("X-Debug: line\nbreak").is_err());
+    }
+
+    #[test]
+    fn error_for_never_shows_secret_values() {
+        register_sensitive("qk_error_test_abcdef123");
+        let body =
+            r#"{"detail":[{"loc":["body","value"],"msg":"too long","input":"hunter2-secret"}]}"#;
+        let rendered = error_for(StatusCode::UNPROCESSABLE_ENTITY, body).to_string();
+        assert!(!rendered.contains("hunter2-secret"), "{}", rendered);
+        assert!(rendered.contains("too long"), "{}", rendered);
+
+        let body = r#"{"detail":"key qk_error_test_abcdef123 was revoked"}"#;
+        let rendered = error_for(StatusCode::FORBIDDEN, body).to_string();
+        assert_eq!(rendered, "API error: key **** was revoked");
+    }
+
+    #[test]
+    fn timing_report_lists_requests_and_totals() {
+        let timing = |method: &str, path: &str, status, ms| Timing {
+            method: method.into(),
+            path: path.into(),
+            status,
+            elapsed: Duration::from_millis(ms),
+        };
+        let report = timing_report(
+            &[
+                timing("GET", "/api/v1/orgs", Some(200), 120),
+                timing("POST", "/api/v1/orgs/x/secrets", None, 30),
+            ],
+            Duration::from_millis(400),
+        );
+        assert_eq!(
+            report,
+            "GET    /api/v1/orgs            200     120 ms\n\
+             POST   /api/v1/orgs/x/secrets  ---      30 ms\n\
+             2 requests took 150 ms of 400 ms total\n"
+        );
+    }
+}
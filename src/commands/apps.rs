@@ -1,12 +1,29 @@
-use clap::{Parser, Subcommand};
+use chrono::Utc;
+use clap::{Parser, Subcommand, ValueEnum};
 use colored::Colorize;
+use serde::Serialize;
+use std::time::Duration;
 use uuid::Uuid;
 
-use crate::api::models::{AppSource, AppSpecCreate, CreateAppRequest, UpdateAppRequest};
+use crate::api::models::{
+    App, AppSource, AppSpecCreate, CreateAppRequest, Deployment, DeploymentStatus, UpdateAppRequest,
+};
+use crate::bulk;
+use crate::cache;
 use crate::client::QuomeClient;
+use crate::commands::{deployments, events};
 use crate::config::Config;
+use crate::context::{self, OrgArgs};
 use crate::errors::{QuomeError, Result};
-use crate::ui::{self, AppRow};
+use crate::registry;
+use crate::ui::{self, AppRow, AppStatusRow, AppWideRow, ContainerRow, EnvRow, TimeStyle};
+use crate::util;
+
+const DEFAULT_PORT: u16 = 8080;
+
+/// How long `--wait-for-deploy` waits for a first deployment to show up
+/// before deciding the app doesn't deploy on its own.
+const FIRST_DEPLOY_GRACE: Duration = Duration::from_secs(60);
 
 #[derive(Subcommand)]
 pub enum AppsCommands {
@@ -24,15 +41,36 @@ pub enum AppsCommands {
 
 #[derive(Parser)]
 pub struct ListArgs {
-    /// Organization ID (uses linked org if not provided)
+    #[command(flatten)]
+    org: OrgArgs,
+
+    /// Add each app's latest deployment status (one extra request per app)
     #[arg(long)]
-    org: Option<Uuid>,
+    status: bool,
+
+    /// Add more per app, comma-separated: spec (JSON only), status (as --status)
+    #[arg(long, value_name = "FIELDS", value_delimiter = ',')]
+    expand: Vec<Expand>,
+
+    /// Serve the last cached result instead of calling the API
+    #[arg(long, conflicts_with_all = ["status", "expand"])]
+    offline: bool,
+
+    /// Show extra columns: organization, tier and description
+    #[arg(short, long, conflicts_with_all = ["status", "json"])]
+    wide: bool,
 
     /// Output as JSON
     #[arg(long)]
     json: bool,
 }
 
+#[derive(Clone, Copy, PartialEq, ValueEnum)]
+enum Expand {
+    Spec,
+    Status,
+}
+
 #[derive(Parser)]
 pub struct CreateArgs {
     /// Application name (lowercase letters, digits, hyphens)
@@ -54,13 +92,24 @@ pub struct CreateArgs {
     #[arg(long, default_value = "main")]
     branch: String,
 
-    /// Container port
-    #[arg(long, default_value = "8080")]
-    port: u16,
+    /// Container image whose exposed port is detected automatically
+    #[arg(long, conflicts_with_all = ["image", "repo"])]
+    from_image: Option<String>,
+
+    /// Container port [default: 8080, or the image's exposed port with --from-image]
+    #[arg(long)]
+    port: Option<u16>,
 
-    /// Organization ID (uses linked org if not provided)
+    /// Stream the first deployment's events until it finishes
     #[arg(long)]
-    org: Option<Uuid>,
+    wait_for_deploy: bool,
+
+    /// Give up waiting after this many seconds
+    #[arg(long, default_value_t = 1800, requires = "wait_for_deploy")]
+    timeout: u64,
+
+    #[command(flatten)]
+    org: OrgArgs,
 
     /// Output as JSON
     #[arg(long)]
@@ -73,9 +122,40 @@ pub struct GetArgs {
     #[arg(short, long)]
     id: Option<Uuid>,
 
-    /// Organization ID (uses linked org if not provided)
+    /// Application name or slug, resolved via the app list
+    #[arg(long, conflicts_with = "id")]
+    app_name: Option<String>,
+
+    /// Keep polling until the app is healthy (running), then print it
     #[arg(long)]
-    org: Option<Uuid>,
+    watch_health: bool,
+
+    /// Show only the app's containers (image and port)
+    #[arg(long)]
+    containers: bool,
+
+    /// Print only the app's public URL (fails if it has none yet)
+    #[arg(long, conflicts_with_all = ["containers", "json"])]
+    url: bool,
+
+    /// Print QUOME_ORG=<id> and QUOME_APP=<id> for a .env file or $GITHUB_ENV
+    #[arg(long, conflicts_with_all = ["containers", "json", "url"])]
+    dotenv: bool,
+
+    /// Also show recent audit events for this app (from the org's last 100)
+    #[arg(long, conflicts_with_all = ["containers", "url", "dotenv"])]
+    events: bool,
+
+    /// Show only the environment the app's containers get (secrets masked)
+    #[arg(long, conflicts_with_all = ["containers", "url", "dotenv", "events", "offline"])]
+    env: bool,
+
+    #[command(flatten)]
+    org: OrgArgs,
+
+    /// Serve the last cached result instead of calling the API
+    #[arg(long, conflicts_with_all = ["watch_health", "events"])]
+    offline: bool,
 
     /// Output as JSON
     #[arg(long)]
@@ -88,6 +168,10 @@ pub struct UpdateArgs {
     #[arg(short, long)]
     id: Option<Uuid>,
 
+    /// Application name or slug, resolved via the app list
+    #[arg(long, conflicts_with = "id")]
+    app_name: Option<String>,
+
     /// New description
     #[arg(long)]
     description: Option<String>,
@@ -96,9 +180,8 @@ pub struct UpdateArgs {
     #[arg(long)]
     branch: Option<String>,
 
-    /// Organization ID (uses linked org if not provided)
-    #[arg(long)]
-    org: Option<Uuid>,
+    #[command(flatten)]
+    org: OrgArgs,
 
     /// Output as JSON
     #[arg(long)]
@@ -108,15 +191,35 @@ pub struct UpdateArgs {
 #[derive(Parser)]
 pub struct DeleteArgs {
     /// Application ID
-    id: Uuid,
+    #[arg(required_unless_present = "all", conflicts_with = "all")]
+    id: Option<Uuid>,
 
-    /// Organization ID (uses linked org if not provided)
+    /// Delete every application matching --filter
     #[arg(long)]
-    org: Option<Uuid>,
+    all: bool,
+
+    /// Only delete applications whose name contains this substring (with --all)
+    #[arg(long, requires = "all")]
+    filter: Option<String>,
+
+    /// Allow --all without --filter (deletes every application in the org)
+    #[arg(long, requires = "all")]
+    really_all: bool,
+
+    /// Stop starting new deletions after the first failure (with --all)
+    #[arg(long, requires = "all")]
+    fail_fast: bool,
+
+    #[command(flatten)]
+    org: OrgArgs,
 
     /// Skip confirmation prompt
     #[arg(short, long)]
     force: bool,
+
+    /// Output the batch result as JSON (with --all)
+    #[arg(long, requires = "all")]
+    json: bool,
 }
 
 pub async fn execute(command: AppsCommands) -> Result<()> {
@@ -140,56 +243,202 @@ fn status_color(status: &str) -> colored::ColoredString {
 }
 
 async fn list(args: ListArgs) -> Result<()> {
+    if args.wide && args.expand.contains(&Expand::Status) {
+        return Err(QuomeError::ApiError(
+            "--wide can't be combined with --expand status".into(),
+        ));
+    }
+    // The table has no spec column, so the per-app lookups would be wasted
+    if args.expand.contains(&Expand::Spec) && !ui::structured(args.json) {
+        return Err(QuomeError::ApiError(
+            "--expand spec needs --json or -o json|yaml".into(),
+        ));
+    }
+
     let config = Config::load()?;
     let token = config.require_token()?;
-
-    let org_id = match args.org {
-        Some(id) => id,
-        None => config.require_linked_org()?,
-    };
-
     let client = QuomeClient::new(Some(&token), None)?;
 
+    let org_id = args
+        .org
+        .resolve_cached(&config, &client, args.offline)
+        .await?;
+
     let sp = ui::spinner("Fetching applications...");
-    let response = client.list_apps(org_id).await?;
+    let mut apps = cache::through(&format!("apps-{}", org_id), args.offline, async {
+        Ok(client.list_apps(org_id).await?.data)
+    })
+    .await?;
     sp.finish_and_clear();
 
-    if args.json {
-        println!("{}", serde_json::to_string_pretty(&response.data)?);
+    // The list endpoint may leave out spec; fetch the full app where it does
+    if args.expand.contains(&Expand::Spec) {
+        let sp = ui::spinner("Fetching app specs...");
+        let missing: Vec<usize> = (0..apps.len())
+            .filter(|&i| apps[i].spec.is_none())
+            .collect();
+        let ids: Vec<Uuid> = missing.iter().map(|&i| apps[i].id).collect();
+        let full = bulk::run(ids, |id| client.get_app(org_id, id)).await;
+        sp.finish_and_clear();
+        for (i, app) in missing.into_iter().zip(full) {
+            // A failed lookup leaves spec null rather than failing the list
+            if let Ok(app) = app {
+                apps[i].spec = app.spec;
+            }
+        }
+    }
+
+    let want_status = args.status || args.expand.contains(&Expand::Status);
+    let deployment_status: Option<Vec<Option<DeploymentStatus>>> = if want_status {
+        let sp = ui::spinner("Fetching deployment status...");
+        let statuses = bulk::run(apps.iter().collect(), |app| {
+            latest_deployment_status(&client, org_id, app.id)
+        })
+        .await;
+        sp.finish_and_clear();
+        // A failed lookup shows as unknown rather than failing the whole list
+        Some(statuses.into_iter().map(|s| s.ok().flatten()).collect())
     } else {
-        if response.data.is_empty() {
+        None
+    };
+
+    if ui::structured(args.json) {
+        match deployment_status {
+            Some(statuses) => {
+                let values = apps
+                    .iter()
+                    .zip(statuses)
+                    .map(|(app, status)| {
+                        let mut value = serde_json::to_value(app)?;
+                        value["deployment_status"] = serde_json::to_value(status)?;
+                        Ok(value)
+                    })
+                    .collect::<Result<Vec<_>>>()?;
+                ui::print_structured(&values)?;
+            }
+            None => ui::print_structured(&apps)?,
+        }
+    } else {
+        if apps.is_empty() {
             println!("No applications found.");
             return Ok(());
         }
 
-        let rows: Vec<AppRow> = response
-            .data
+        if args.wide {
+            let rows: Vec<AppWideRow> = apps
+                .iter()
+                .map(|app| AppWideRow {
+                    id: app.id.to_string(),
+                    name: app.name.clone(),
+                    status: status_color(&app.status).to_string(),
+                    url: app
+                        .url()
+                        .map_or("-".to_string(), |url| ui::hyperlink(&url, &url)),
+                    org: app.organization_id.to_string(),
+                    tier: app.resource_tier.clone().unwrap_or_else(|| "-".to_string()),
+                    description: app.description.clone().unwrap_or_else(|| "-".to_string()),
+                    created: ui::fmt_time(&app.created_at, TimeStyle::List),
+                })
+                .collect();
+            ui::print_table(rows);
+            return Ok(());
+        }
+
+        let rows: Vec<AppRow> = apps
             .iter()
             .map(|app| AppRow {
                 id: app.id.to_string(),
                 name: app.name.clone(),
                 status: status_color(&app.status).to_string(),
-                url: app.primary_url.clone().unwrap_or_else(|| "-".to_string()),
-                created: app.created_at.format("%Y-%m-%d %H:%M").to_string(),
+                url: app
+                    .url()
+                    .map_or("-".to_string(), |url| ui::hyperlink(&url, &url)),
+                created: ui::fmt_time(&app.created_at, TimeStyle::List),
             })
             .collect();
 
-        ui::print_table(rows);
+        match deployment_status {
+            Some(statuses) => ui::print_table(
+                rows.into_iter()
+                    .zip(statuses)
+                    .map(|(row, status)| AppStatusRow {
+                        id: row.id,
+                        name: row.name,
+                        status: row.status,
+                        deployment: status
+                            .map(|s| deployments::status_color(&s).to_string())
+                            .unwrap_or_else(|| "-".to_string()),
+                        url: row.url,
+                        created: row.created,
+                    })
+                    .collect(),
+            ),
+            None => ui::print_table(rows),
+        }
     }
 
     Ok(())
 }
 
+/// Status of the app's newest deployment, or `None` if it has never deployed.
+async fn latest_deployment_status(
+    client: &QuomeClient,
+    org_id: Uuid,
+    app_id: Uuid,
+) -> Result<Option<DeploymentStatus>> {
+    let deployments = client.list_deployments(org_id, app_id).await?.data;
+    Ok(deployments
+        .into_iter()
+        .max_by_key(|d| d.created_at)
+        .map(|d| d.status))
+}
+
+/// Port for `--from-image`: the image's single exposed port, else the default.
+async fn detect_port(image: &str) -> u16 {
+    let sp = ui::spinner("Inspecting image...");
+    let ports = registry::exposed_ports(image).await;
+    sp.finish_and_clear();
+
+    let note = match ports {
+        Ok(ports) if ports.len() == 1 => {
+            eprintln!(
+                "{}",
+                format!("Detected port {} from image", ports[0]).dimmed()
+            );
+            return ports[0];
+        }
+        Ok(ports) if ports.is_empty() => "image exposes no ports".to_string(),
+        Ok(ports) => format!(
+            "image exposes several ports ({}); pass --port to choose",
+            ports
+                .iter()
+                .map(u16::to_string)
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+        Err(e) => format!("couldn't inspect image: {}", e),
+    };
+    eprintln!(
+        "{}",
+        format!("{}; using port {}", note, DEFAULT_PORT).yellow()
+    );
+    DEFAULT_PORT
+}
+
 async fn create(args: CreateArgs) -> Result<()> {
     let config = Config::load()?;
     let token = config.require_token()?;
+    let client = QuomeClient::new(Some(&token), None)?;
+
+    let org_id = args.org.resolve(&config, &client).await?;
 
-    let org_id = match args.org {
-        Some(id) => id,
-        None => config.require_linked_org()?,
+    let port = match (args.port, &args.from_image) {
+        (Some(port), _) => port,
+        (None, Some(image)) => detect_port(image).await,
+        (None, None) => DEFAULT_PORT,
     };
 
-    let source = if let Some(image) = args.image {
+    let source = if let Some(image) = args.image.or(args.from_image) {
         AppSource::Image { image_url: image }
     } else if let Some(repo) = args.repo {
         let (owner, name) = repo
@@ -206,8 +455,6 @@ async fn create(args: CreateArgs) -> Result<()> {
         ));
     };
 
-    let client = QuomeClient::new(Some(&token), None)?;
-
     let sp = ui::spinner("Creating application...");
     let app = client
         .create_app(
@@ -217,7 +464,7 @@ async fn create(args: CreateArgs) -> Result<()> {
                 description: args.description,
                 source,
                 spec: AppSpecCreate {
-                    port: Some(args.port),
+                    port: Some(port),
                     ..Default::default()
                 },
             },
@@ -225,9 +472,8 @@ async fn create(args: CreateArgs) -> Result<()> {
         .await?;
     sp.finish_and_clear();
 
-    if args.json {
-        println!("{}", serde_json::to_string_pretty(&app)?);
-    } else {
+    let json = ui::structured(args.json);
+    if !json {
         ui::print_success(
             "Created application",
             &[
@@ -237,32 +483,133 @@ async fn create(args: CreateArgs) -> Result<()> {
             ],
         );
     }
+    if !args.wait_for_deploy {
+        if json {
+            ui::print_structured(&app)?;
+        }
+        return Ok(());
+    }
 
-    Ok(())
+    let deployment = wait_for_first_deploy(&client, org_id, &app, args.timeout, json).await?;
+    if json {
+        let value = serde_json::json!({ "app": app, "deployment": deployment });
+        ui::print_structured(&value)?;
+    }
+    match deployment {
+        Some(ref d) => deployments::succeeded(d, json),
+        None => Ok(()),
+    }
+}
+
+/// Wait for the app's first deployment to appear and follow it to the end.
+/// `None` if nothing starts within [`FIRST_DEPLOY_GRACE`].
+async fn wait_for_first_deploy(
+    client: &QuomeClient,
+    org_id: Uuid,
+    app: &App,
+    timeout: u64,
+    quiet: bool,
+) -> Result<Option<Deployment>> {
+    let sp = ui::spinner("Waiting for the first deployment to start...");
+    let started = util::poll_until(
+        util::POLL_INTERVAL,
+        FIRST_DEPLOY_GRACE,
+        || async { Ok(client.list_deployments(org_id, app.id).await?.data) },
+        |deployments: &Vec<Deployment>| !deployments.is_empty(),
+    )
+    .await;
+    sp.finish_and_clear();
+
+    let first = match started {
+        Ok(deployments) => deployments.into_iter().min_by_key(|d| d.created_at),
+        Err(QuomeError::Timeout(_)) => None,
+        Err(e) => return Err(e),
+    };
+    let Some(first) = first else {
+        eprintln!(
+            "No deployment started within {}s; {} may not deploy on its own. Start one with `quome deployments create --app {}`.",
+            FIRST_DEPLOY_GRACE.as_secs(),
+            app.name,
+            app.id
+        );
+        return Ok(None);
+    };
+
+    if !quiet {
+        println!();
+    }
+    let deployment = deployments::follow(
+        client,
+        org_id,
+        app.id,
+        first.id,
+        Duration::from_secs(timeout),
+        quiet,
+    )
+    .await?;
+    Ok(Some(deployment))
 }
 
 async fn get(args: GetArgs) -> Result<()> {
     let config = Config::load()?;
     let token = config.require_token()?;
+    let client = QuomeClient::new(Some(&token), None)?;
+
+    let org_id = args
+        .org
+        .resolve_cached(&config, &client, args.offline)
+        .await?;
 
-    let org_id = match args.org {
-        Some(id) => id,
-        None => config.require_linked_org()?,
+    let app_id = if args.offline {
+        context::resolve_app_offline(&config, org_id, args.id, args.app_name.as_deref())?
+    } else {
+        context::resolve_app(&config, &client, org_id, args.id, args.app_name.as_deref()).await?
     };
 
-    let app_id = match args.id {
-        Some(id) => id,
-        None => config.require_linked_app()?,
+    let app = if args.watch_health {
+        watch_health(&client, org_id, app_id).await?
+    } else {
+        let sp = ui::spinner("Fetching application...");
+        let key = format!("app-{}", app_id);
+        let app = cache::through(&key, args.offline, client.get_app(org_id, app_id)).await?;
+        sp.finish_and_clear();
+        app
     };
 
-    let client = QuomeClient::new(Some(&token), None)?;
+    if args.containers {
+        return print_containers(&app, ui::structured(args.json));
+    }
+    if args.env {
+        return print_env(&client, org_id, &app, ui::structured(args.json)).await;
+    }
+    if args.dotenv {
+        println!("QUOME_ORG={}", app.organization_id);
+        println!("QUOME_APP={}", app.id);
+        return Ok(());
+    }
+    if args.url {
+        let url = app
+            .url()
+            .ok_or_else(|| QuomeError::NotFound(format!("{} has no public URL yet", app.name)))?;
+        println!("{}", url);
+        return Ok(());
+    }
 
-    let sp = ui::spinner("Fetching application...");
-    let app = client.get_app(org_id, app_id).await?;
-    sp.finish_and_clear();
+    let recent = if args.events {
+        let sp = ui::spinner("Fetching audit events...");
+        let recent = events::recent_for_resource(&client, org_id, &app.id.to_string()).await;
+        sp.finish_and_clear();
+        Some(recent?)
+    } else {
+        None
+    };
 
-    if args.json {
-        println!("{}", serde_json::to_string_pretty(&app)?);
+    if ui::structured(args.json) {
+        let mut value = serde_json::to_value(&app)?;
+        if let Some(recent) = recent {
+            value["events"] = serde_json::to_value(recent)?;
+        }
+        ui::print_structured(&value)?;
     } else {
         let mut details = vec![
             ("ID", app.id.to_string()),
@@ -277,51 +624,182 @@ async fn get(args: GetArgs) -> Result<()> {
             details.push(("Source", source_type.clone()));
         }
         if let (Some(owner), Some(name)) = (&app.github_repo_owner, &app.github_repo_name) {
-            details.push(("Repo", format!("{}/{}", owner, name)));
+            let repo = format!("{}/{}", owner, name);
+            let link = format!("https://github.com/{}", repo);
+            details.push(("Repo", ui::hyperlink(&repo, &link)));
         }
         if let Some(ref image) = app.container_image_url {
             details.push(("Image", image.clone()));
         }
-        if let Some(ref url) = app.primary_url {
-            details.push(("URL", url.clone()));
+        if let Some(url) = app.url() {
+            details.push(("URL", ui::hyperlink(&url, &url)));
         }
         if let Some(ref domain) = app.custom_domain {
             details.push(("Custom domain", domain.clone()));
         }
 
-        details.push((
-            "Created",
-            app.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
-        ));
-        details.push((
-            "Updated",
-            app.updated_at.format("%Y-%m-%d %H:%M:%S").to_string(),
-        ));
+        details.push(("Created", ui::fmt_time(&app.created_at, TimeStyle::Detail)));
+        details.push(("Updated", ui::fmt_time(&app.updated_at, TimeStyle::Detail)));
 
         let details_ref: Vec<(&str, &str)> =
             details.iter().map(|(k, v)| (*k, v.as_str())).collect();
 
         ui::print_detail(&app.name, &details_ref);
+
+        match recent {
+            Some(recent) if recent.is_empty() => {
+                println!();
+                println!("No recent events for this app.");
+            }
+            Some(recent) => {
+                println!();
+                println!("{}", "Recent events".bold());
+                ui::print_table(events::event_rows(&recent));
+            }
+            None => {}
+        }
+    }
+
+    Ok(())
+}
+
+fn print_containers(app: &App, json: bool) -> Result<()> {
+    let containers = app.containers();
+    if json {
+        ui::print_structured(&containers)?;
+        return Ok(());
+    }
+    if containers.is_empty() {
+        println!("No containers in this app's spec.");
+        return Ok(());
     }
 
+    let rows: Vec<ContainerRow> = containers
+        .into_iter()
+        .map(|c| ContainerRow {
+            name: c.name.unwrap_or_else(|| "-".to_string()),
+            image: c.image,
+            port: c.port.map_or("-".to_string(), |p| p.to_string()),
+        })
+        .collect();
+    ui::print_table(rows);
     Ok(())
 }
 
+/// One variable of an app's effective environment.
+#[derive(Serialize)]
+struct EnvVar {
+    name: String,
+    /// The plain value, or `->secret:NAME` for an injected secret
+    value: String,
+    source: &'static str,
+}
+
+/// The app's plain `spec.env_vars` plus the org secrets injected alongside
+/// them at deploy time. Secret values are never fetched.
+async fn print_env(client: &QuomeClient, org_id: Uuid, app: &App, json: bool) -> Result<()> {
+    let sp = ui::spinner("Fetching secrets...");
+    let secrets = client.list_secrets(org_id).await;
+    sp.finish_and_clear();
+    let secrets = secrets?.data;
+
+    let mut vars: Vec<EnvVar> = app
+        .env_vars()
+        .into_iter()
+        .map(|(name, value)| EnvVar {
+            name,
+            value,
+            source: "plain",
+        })
+        .chain(secrets.into_iter().map(|s| EnvVar {
+            value: format!("->secret:{}", s.name),
+            name: s.name,
+            source: "secret",
+        }))
+        .collect();
+    vars.sort_by(|a, b| a.name.cmp(&b.name).then(a.source.cmp(b.source)));
+
+    if json {
+        ui::print_structured(&vars)?;
+        return Ok(());
+    }
+    if vars.is_empty() {
+        println!("No environment variables or secrets for {}.", app.name);
+        return Ok(());
+    }
+
+    let rows: Vec<EnvRow> = vars
+        .into_iter()
+        .map(|v| EnvRow {
+            name: v.name,
+            value: v.value,
+            source: v.source.to_string(),
+        })
+        .collect();
+    ui::print_table(rows);
+    Ok(())
+}
+
+/// Poll the app until it reports `running`, keeping a live healthy/unhealthy
+/// indicator on the spinner and logging each status change to stderr. An app
+/// already `failed` is watched until it recovers; one that turns `failed`
+/// during the watch ends it with an error.
+async fn watch_health(client: &QuomeClient, org_id: Uuid, app_id: Uuid) -> Result<App> {
+    let sp = ui::spinner("Checking health...");
+    let mut last: Option<String> = None;
+    let mut failed = false;
+    let app = util::poll_until(
+        util::POLL_INTERVAL,
+        Duration::MAX,
+        || client.get_app(org_id, app_id),
+        |app| {
+            if last.as_deref() != Some(app.status.as_str()) {
+                sp.suspend(|| {
+                    eprintln!(
+                        "  {} {} {}",
+                        ui::fmt_time(&Utc::now(), TimeStyle::Clock).dimmed(),
+                        "•".cyan(),
+                        status_color(&app.status)
+                    )
+                });
+                failed = last.is_some() && app.status == "failed";
+                last = Some(app.status.clone());
+            }
+            let healthy = app.status == "running";
+            if !healthy {
+                sp.set_message(format!(
+                    "{} {} ({}), watching... Ctrl-C to stop",
+                    "●".red(),
+                    app.name,
+                    app.status
+                ));
+            }
+            healthy || failed
+        },
+    )
+    .await;
+    sp.finish_and_clear();
+
+    let app = app?;
+    if failed {
+        return Err(QuomeError::ApiError(format!(
+            "application {} failed while being watched",
+            app.name
+        )));
+    }
+    eprintln!("{} {} is healthy", "●".green(), app.name);
+    Ok(app)
+}
+
 async fn update(args: UpdateArgs) -> Result<()> {
     let config = Config::load()?;
     let token = config.require_token()?;
+    let client = QuomeClient::new(Some(&token), None)?;
 
-    let org_id = match args.org {
-        Some(id) => id,
-        None => config.require_linked_org()?,
-    };
+    let org_id = args.org.resolve(&config, &client).await?;
 
-    let app_id = match args.id {
-        Some(id) => id,
-        None => config.require_linked_app()?,
-    };
-
-    let client = QuomeClient::new(Some(&token), None)?;
+    let app_id =
+        context::resolve_app(&config, &client, org_id, args.id, args.app_name.as_deref()).await?;
 
     let sp = ui::spinner("Updating application...");
     let app = client
@@ -336,8 +814,8 @@ async fn update(args: UpdateArgs) -> Result<()> {
         .await?;
     sp.finish_and_clear();
 
-    if args.json {
-        println!("{}", serde_json::to_string_pretty(&app)?);
+    if ui::structured(args.json) {
+        ui::print_structured(&app)?;
     } else {
         ui::print_success(
             "Updated application",
@@ -351,16 +829,18 @@ async fn update(args: UpdateArgs) -> Result<()> {
 async fn delete(args: DeleteArgs) -> Result<()> {
     let config = Config::load()?;
     let token = config.require_token()?;
+    let client = QuomeClient::new(Some(&token), None)?;
 
-    let org_id = match args.org {
-        Some(id) => id,
-        None => config.require_linked_org()?,
+    let org_id = args.org.resolve(&config, &client).await?;
+
+    let Some(app_id) = args.id else {
+        return delete_all(args, org_id, &client).await;
     };
 
     if !args.force {
         let confirm = inquire::Confirm::new(&format!(
             "Are you sure you want to delete application {}?",
-            args.id
+            app_id
         ))
         .with_default(false)
         .prompt()
@@ -372,13 +852,47 @@ async fn delete(args: DeleteArgs) -> Result<()> {
         }
     }
 
-    let client = QuomeClient::new(Some(&token), None)?;
-
     let sp = ui::spinner("Deleting application...");
-    client.delete_app(org_id, args.id).await?;
+    client.delete_app(org_id, app_id).await?;
     sp.finish_and_clear();
 
-    ui::print_success("Deleted application", &[("ID", &args.id.to_string())]);
+    ui::print_success("Deleted application", &[("ID", &app_id.to_string())]);
 
     Ok(())
 }
+
+async fn delete_all(args: DeleteArgs, org_id: Uuid, client: &QuomeClient) -> Result<()> {
+    let sp = ui::spinner("Fetching applications...");
+    let response = client.list_apps(org_id).await?;
+    sp.finish_and_clear();
+
+    let apps = bulk::select(
+        response.data,
+        |a| &a.name,
+        args.filter.as_deref(),
+        args.really_all,
+        "application",
+    )?;
+
+    if apps.is_empty() {
+        println!("No matching applications found.");
+        return Ok(());
+    }
+
+    let names: Vec<String> = apps
+        .iter()
+        .map(|a| format!("{} ({})", a.name, a.id))
+        .collect();
+
+    if !bulk::confirm("Delete", "applications", &names, args.force)? {
+        println!("Cancelled.");
+        return Ok(());
+    }
+
+    let items = names.into_iter().zip(apps.iter().map(|a| a.id)).collect();
+    bulk::run_batch("Deleted", items, args.fail_fast, |id| {
+        client.delete_app(org_id, id)
+    })
+    .await
+    .report(ui::structured(args.json))
+}
@@ -0,0 +1,107 @@
+use clap::{Parser, Subcommand};
+
+use crate::cache;
+use crate::errors::Result;
+use crate::ui;
+
+#[derive(Subcommand)]
+pub enum CacheCommands {
+    /// Show cache location, entry count and size
+    Info(InfoArgs),
+    /// Remove all cached data
+    Clear(ClearArgs),
+}
+
+#[derive(Parser)]
+pub struct InfoArgs {
+    /// Output as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+#[derive(Parser)]
+pub struct ClearArgs {
+    /// Output as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+pub async fn execute(command: CacheCommands) -> Result<()> {
+    match command {
+        CacheCommands::Info(args) => info(args).await,
+        CacheCommands::Clear(args) => clear(args).await,
+    }
+}
+
+/// Human-readable byte count (1024-based, one decimal).
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
+
+async fn info(args: InfoArgs) -> Result<()> {
+    let dir = cache::dir()?;
+    let stats = cache::stats()?;
+
+    if ui::structured(args.json) {
+        ui::print_structured(&serde_json::json!({
+            "path": dir,
+            "entries": stats.entries,
+            "bytes": stats.bytes,
+        }))?;
+    } else {
+        ui::print_detail(
+            "Cache",
+            &[
+                ("Path", &dir.display().to_string()),
+                ("Entries", &stats.entries.to_string()),
+                ("Size", &format_bytes(stats.bytes)),
+            ],
+        );
+    }
+
+    Ok(())
+}
+
+async fn clear(args: ClearArgs) -> Result<()> {
+    let removed = cache::clear()?;
+
+    if ui::structured(args.json) {
+        ui::print_structured(&removed)?;
+    } else if removed.entries == 0 {
+        println!("Cache is already empty.");
+    } else {
+        ui::print_success(
+            "Cleared cache",
+            &[
+                ("Entries", &removed.entries.to_string()),
+                ("Freed", &format_bytes(removed.bytes)),
+            ],
+        );
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::format_bytes;
+
+    #[test]
+    fn format_bytes_units() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1536), "1.5 KB");
+        assert_eq!(format_bytes(5 * 1024 * 1024), "5.0 MB");
+    }
+}
@@ -0,0 +1,132 @@
+use clap::Parser;
+use clap_complete::Shell;
+use colored::Colorize;
+use std::path::{Path, PathBuf};
+
+use crate::errors::{QuomeError, Result};
+
+#[derive(Parser)]
+pub struct Args {
+    /// Shell to generate completions for (detected from $SHELL if omitted)
+    shell: Option<Shell>,
+
+    /// Write the script to the shell's completion directory instead of stdout
+    #[arg(long)]
+    install: bool,
+}
+
+pub fn execute(args: Args, mut cmd: clap::Command) -> Result<()> {
+    let Some(shell) = args.shell.or_else(Shell::from_env) else {
+        return Err(QuomeError::UnknownShell(
+            std::env::var("SHELL").unwrap_or_default(),
+        ));
+    };
+    let name = cmd.get_name().to_string();
+
+    if !args.install {
+        clap_complete::generate(shell, &mut cmd, name, &mut std::io::stdout());
+        return Ok(());
+    }
+
+    let home = dirs::home_dir().ok_or_else(|| {
+        QuomeError::Io(std::io::Error::other("could not determine home directory"))
+    })?;
+    let xdg = |var: &str, default: &str| {
+        std::env::var_os(var)
+            .map(PathBuf::from)
+            .filter(|p| p.is_absolute())
+            .unwrap_or_else(|| home.join(default))
+    };
+    let (path, rc) = install_target(
+        shell,
+        &home,
+        &xdg("XDG_DATA_HOME", ".local/share"),
+        &xdg("XDG_CONFIG_HOME", ".config"),
+    );
+
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let mut script = Vec::new();
+    clap_complete::generate(shell, &mut cmd, name, &mut script);
+    std::fs::write(&path, script)?;
+
+    println!(
+        "{} Installed {} completions to {}",
+        "Success!".green().bold(),
+        shell,
+        path.display()
+    );
+    match rc {
+        Some((file, line)) => {
+            println!();
+            println!("Add this to {} if it isn't there already:", file);
+            println!("  {}", line.cyan());
+        }
+        None => println!("Open a new shell to start using them."),
+    }
+    Ok(())
+}
+
+/// Where each shell looks for completion scripts, and the rc-file line (if
+/// any) needed to load them. Bash and fish pick the file up on their own.
+fn install_target(
+    shell: Shell,
+    home: &Path,
+    data_dir: &Path,
+    config_dir: &Path,
+) -> (PathBuf, Option<(&'static str, String)>) {
+    match shell {
+        Shell::Bash => (data_dir.join("bash-completion/completions/quome"), None),
+        Shell::Zsh => {
+            let dir = home.join(".zsh/completions");
+            let line = format!(
+                "fpath=({} $fpath); autoload -Uz compinit && compinit",
+                dir.display()
+            );
+            (dir.join("_quome"), Some(("~/.zshrc", line)))
+        }
+        Shell::Fish => (config_dir.join("fish/completions/quome.fish"), None),
+        Shell::Elvish => (
+            config_dir.join("elvish/lib/quome.elv"),
+            Some(("~/.config/elvish/rc.elv", "use quome".to_string())),
+        ),
+        // PowerShell has no completion directory; the profile dot-sources it
+        _ => {
+            let path = config_dir.join("powershell/quome.ps1");
+            let line = format!(". {}", path.display());
+            (path, Some(("$PROFILE", line)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::install_target;
+    use clap_complete::Shell;
+    use std::path::Path;
+
+    #[test]
+    fn install_target_uses_each_shells_directory() {
+        let home = Path::new("/home/ada");
+        let data = Path::new("/home/ada/.local/share");
+        let config = Path::new("/xdg/config");
+
+        let (bash, rc) = install_target(Shell::Bash, home, data, config);
+        assert_eq!(
+            bash,
+            Path::new("/home/ada/.local/share/bash-completion/completions/quome")
+        );
+        assert!(rc.is_none());
+
+        let (zsh, rc) = install_target(Shell::Zsh, home, data, config);
+        assert_eq!(zsh, Path::new("/home/ada/.zsh/completions/_quome"));
+        assert!(rc
+            .unwrap()
+            .1
+            .starts_with("fpath=(/home/ada/.zsh/completions "));
+
+        let (fish, _) = install_target(Shell::Fish, home, data, config);
+        assert_eq!(fish, Path::new("/xdg/config/fish/completions/quome.fish"));
+    }
+}
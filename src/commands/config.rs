@@ -0,0 +1,51 @@
+use clap::{Parser, Subcommand};
+
+use crate::config::{Config, SCHEMA_VERSION};
+use crate::errors::Result;
+use crate::ui;
+
+#[derive(Subcommand)]
+pub enum ConfigCommands {
+    /// Upgrade ~/.quome/config.json to the current schema version
+    Migrate(MigrateArgs),
+}
+
+#[derive(Parser)]
+pub struct MigrateArgs {
+    /// Output as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+pub async fn execute(command: ConfigCommands) -> Result<()> {
+    match command {
+        ConfigCommands::Migrate(args) => migrate(args).await,
+    }
+}
+
+async fn migrate(args: MigrateArgs) -> Result<()> {
+    let (_, migration) = Config::load_migrated()?;
+    let from = migration.as_ref().map_or(SCHEMA_VERSION, |m| m.from);
+    let backup = migration.map(|m| m.backup.display().to_string());
+
+    if ui::structured(args.json) {
+        ui::print_structured(&serde_json::json!({
+            "from": from,
+            "to": SCHEMA_VERSION,
+            "backup": backup,
+        }))?;
+    } else if let Some(backup) = backup {
+        ui::print_success(
+            "Migrated config",
+            &[
+                ("From", &format!("schema version {}", from)),
+                ("To", &format!("schema version {}", SCHEMA_VERSION)),
+                ("Backup", &backup),
+            ],
+        );
+    } else {
+        println!("Config is already at schema version {}.", SCHEMA_VERSION);
+    }
+
+    Ok(())
+}
@@ -1,12 +1,19 @@
 use clap::{Parser, Subcommand};
 use colored::Colorize;
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use uuid::Uuid;
 
-use crate::api::models::{CreateDatabaseRequest, UpdateDatabaseRequest};
+use crate::api::models::{CreateDatabaseRequest, Database, UpdateDatabaseRequest};
+use crate::bulk;
+use crate::cache;
 use crate::client::QuomeClient;
 use crate::config::Config;
-use crate::errors::Result;
-use crate::ui::{self, DatabaseRow};
+use crate::context::OrgArgs;
+use crate::errors::{QuomeError, Result};
+use crate::ui::{self, DatabaseRow, DatabaseWideRow, TimeStyle};
+use crate::util;
 
 #[derive(Subcommand)]
 pub enum DatabasesCommands {
@@ -24,9 +31,20 @@ pub enum DatabasesCommands {
 
 #[derive(Parser)]
 pub struct ListArgs {
-    /// Organization ID (uses linked org if not provided)
+    #[command(flatten)]
+    org: OrgArgs,
+
+    /// Only show databases in these states, e.g. failed,provisioning
+    #[arg(long, visible_alias = "state", value_delimiter = ',')]
+    status: Vec<String>,
+
+    /// Serve the last cached result instead of calling the API
     #[arg(long)]
-    org: Option<Uuid>,
+    offline: bool,
+
+    /// Show extra columns: storage, HA and private IP
+    #[arg(short, long, conflicts_with = "json")]
+    wide: bool,
 
     /// Output as JSON
     #[arg(long)]
@@ -35,46 +53,74 @@ pub struct ListArgs {
 
 #[derive(Parser)]
 pub struct CreateArgs {
-    /// Database name
-    name: String,
+    /// Database name (or `name` in --file)
+    #[arg(required_unless_present = "file")]
+    name: Option<String>,
+
+    /// YAML or JSON manifest with the database settings; flags override it
+    #[arg(long, visible_alias = "from-file", value_name = "PATH")]
+    file: Option<PathBuf>,
 
     /// Database description
     #[arg(long)]
     description: Option<String>,
 
-    /// PostgreSQL major version
-    #[arg(long, default_value = "17")]
-    version: String,
-
-    /// Instance tier (e.g., db-f1-micro)
-    #[arg(long, default_value = "db-f1-micro")]
-    tier: String,
-
-    /// Storage in GB
-    #[arg(long, default_value = "10")]
-    storage_gb: i32,
+    /// PostgreSQL major version [default: 17]
+    #[arg(long)]
+    version: Option<String>,
 
-    /// Enable high availability
+    /// Instance tier (e.g., db-f1-micro) [default: db-f1-micro]
     #[arg(long)]
-    ha: bool,
+    tier: Option<String>,
 
-    /// Organization ID (uses linked org if not provided)
+    /// Storage in GB [default: 10]
     #[arg(long)]
-    org: Option<Uuid>,
+    storage_gb: Option<i32>,
+
+    /// Enable high availability; `--ha=false` turns it off over the manifest
+    #[arg(long, num_args = 0..=1, require_equals = true, default_missing_value = "true")]
+    ha: Option<bool>,
+
+    #[command(flatten)]
+    org: OrgArgs,
 
     /// Output as JSON
     #[arg(long)]
     json: bool,
 }
 
+/// `db create --file`: the same settings as the flags, all optional so a
+/// shared manifest can leave e.g. the name to each environment.
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+struct DatabaseManifest {
+    name: Option<String>,
+    description: Option<String>,
+    version: Option<String>,
+    tier: Option<String>,
+    storage_gb: Option<i32>,
+    ha_enabled: Option<bool>,
+}
+
+impl DatabaseManifest {
+    fn load(path: &Path) -> Result<Self> {
+        let raw = std::fs::read_to_string(path)?;
+        serde_yaml::from_str(&raw)
+            .map_err(|e| QuomeError::InvalidManifest(format!("{}: {}", path.display(), e)))
+    }
+}
+
 #[derive(Parser)]
 pub struct GetArgs {
     /// Database ID
     id: Uuid,
 
-    /// Organization ID (uses linked org if not provided)
+    #[command(flatten)]
+    org: OrgArgs,
+
+    /// Serve the last cached result instead of calling the API
     #[arg(long)]
-    org: Option<Uuid>,
+    offline: bool,
 
     /// Output as JSON
     #[arg(long)]
@@ -102,9 +148,16 @@ pub struct UpdateArgs {
     #[arg(long)]
     ha: Option<bool>,
 
-    /// Organization ID (uses linked org if not provided)
+    /// Wait until the database is running again
     #[arg(long)]
-    org: Option<Uuid>,
+    wait: bool,
+
+    /// Give up waiting after this many seconds
+    #[arg(long, default_value_t = 1800, requires = "wait")]
+    timeout: u64,
+
+    #[command(flatten)]
+    org: OrgArgs,
 
     /// Output as JSON
     #[arg(long)]
@@ -114,15 +167,35 @@ pub struct UpdateArgs {
 #[derive(Parser)]
 pub struct DeleteArgs {
     /// Database ID
-    id: Uuid,
+    #[arg(required_unless_present = "all", conflicts_with = "all")]
+    id: Option<Uuid>,
 
-    /// Organization ID (uses linked org if not provided)
+    /// Delete every database matching --filter
     #[arg(long)]
-    org: Option<Uuid>,
+    all: bool,
+
+    /// Only delete databases whose name contains this substring (with --all)
+    #[arg(long, requires = "all")]
+    filter: Option<String>,
+
+    /// Allow --all without --filter (deletes every database in the org)
+    #[arg(long, requires = "all")]
+    really_all: bool,
+
+    /// Stop starting new deletions after the first failure (with --all)
+    #[arg(long, requires = "all")]
+    fail_fast: bool,
+
+    #[command(flatten)]
+    org: OrgArgs,
 
     /// Skip confirmation prompt
     #[arg(short, long)]
     force: bool,
+
+    /// Output the batch result as JSON (with --all)
+    #[arg(long, requires = "all")]
+    json: bool,
 }
 
 pub async fn execute(command: DatabasesCommands) -> Result<()> {
@@ -148,28 +221,63 @@ fn status_color(status: &str) -> colored::ColoredString {
 async fn list(args: ListArgs) -> Result<()> {
     let config = Config::load()?;
     let token = config.require_token()?;
-
-    let org_id = match args.org {
-        Some(id) => id,
-        None => config.require_linked_org()?,
-    };
-
     let client = QuomeClient::new(Some(&token), None)?;
 
+    let org_id = args
+        .org
+        .resolve_cached(&config, &client, args.offline)
+        .await?;
+
     let sp = ui::spinner("Fetching databases...");
-    let response = client.list_databases(org_id).await?;
+    let databases = cache::through(&format!("databases-{}", org_id), args.offline, async {
+        Ok(client.list_databases(org_id).await?.data)
+    })
+    .await?;
     sp.finish_and_clear();
 
-    if args.json {
-        println!("{}", serde_json::to_string_pretty(&response.data)?);
+    let databases: Vec<_> = databases
+        .into_iter()
+        .filter(|db| {
+            args.status.is_empty()
+                || args
+                    .status
+                    .iter()
+                    .any(|s| s.eq_ignore_ascii_case(&db.status))
+        })
+        .collect();
+
+    if ui::structured(args.json) {
+        ui::print_structured(&databases)?;
     } else {
-        if response.data.is_empty() {
-            println!("No databases found.");
+        if databases.is_empty() {
+            if args.status.is_empty() {
+                println!("No databases found.");
+            } else {
+                println!("No databases with status {}.", args.status.join(" or "));
+            }
+            return Ok(());
+        }
+
+        if args.wide {
+            let rows: Vec<DatabaseWideRow> = databases
+                .iter()
+                .map(|db| DatabaseWideRow {
+                    id: db.id.to_string(),
+                    name: db.name.clone(),
+                    version: format!("PG {}", db.version),
+                    tier: db.tier.clone(),
+                    storage: format!("{} GB", db.storage_gb),
+                    ha: if db.ha_enabled { "yes" } else { "no" }.to_string(),
+                    private_ip: db.private_ip.clone().unwrap_or_else(|| "-".to_string()),
+                    status: status_color(&db.status).to_string(),
+                    created: ui::fmt_time(&db.created_at, TimeStyle::List),
+                })
+                .collect();
+            ui::print_table(rows);
             return Ok(());
         }
 
-        let rows: Vec<DatabaseRow> = response
-            .data
+        let rows: Vec<DatabaseRow> = databases
             .iter()
             .map(|db| DatabaseRow {
                 id: db.id.to_string(),
@@ -177,7 +285,7 @@ async fn list(args: ListArgs) -> Result<()> {
                 version: format!("PG {}", db.version),
                 tier: db.tier.clone(),
                 status: status_color(&db.status).to_string(),
-                created: db.created_at.format("%Y-%m-%d %H:%M").to_string(),
+                created: ui::fmt_time(&db.created_at, TimeStyle::List),
             })
             .collect();
 
@@ -190,29 +298,22 @@ async fn list(args: ListArgs) -> Result<()> {
 async fn create(args: CreateArgs) -> Result<()> {
     let config = Config::load()?;
     let token = config.require_token()?;
-
-    let org_id = match args.org {
-        Some(id) => id,
-        None => config.require_linked_org()?,
-    };
-
     let client = QuomeClient::new(Some(&token), None)?;
 
-    let req = CreateDatabaseRequest {
-        name: args.name.clone(),
-        description: args.description,
-        version: args.version,
-        tier: args.tier,
-        storage_gb: args.storage_gb,
-        ha_enabled: args.ha,
+    let org_id = args.org.resolve(&config, &client).await?;
+
+    let manifest = match &args.file {
+        Some(path) => DatabaseManifest::load(path)?,
+        None => DatabaseManifest::default(),
     };
+    let req = create_request(&args, manifest)?;
 
     let sp = ui::spinner("Creating database...");
     let db = client.create_database(org_id, &req).await?;
     sp.finish_and_clear();
 
-    if args.json {
-        println!("{}", serde_json::to_string_pretty(&db)?);
+    if ui::structured(args.json) {
+        ui::print_structured(&db)?;
     } else {
         ui::print_success(
             "Created database",
@@ -227,23 +328,50 @@ async fn create(args: CreateArgs) -> Result<()> {
     Ok(())
 }
 
+/// Flags win over the manifest; anything still unset gets the flag default.
+fn create_request(args: &CreateArgs, manifest: DatabaseManifest) -> Result<CreateDatabaseRequest> {
+    let name = args.name.clone().or(manifest.name).ok_or_else(|| {
+        QuomeError::ApiError("the manifest has no `name`; pass it as an argument".into())
+    })?;
+    Ok(CreateDatabaseRequest {
+        name,
+        description: args.description.clone().or(manifest.description),
+        version: args
+            .version
+            .clone()
+            .or(manifest.version)
+            .unwrap_or_else(|| "17".into()),
+        tier: args
+            .tier
+            .clone()
+            .or(manifest.tier)
+            .unwrap_or_else(|| "db-f1-micro".into()),
+        storage_gb: args.storage_gb.or(manifest.storage_gb).unwrap_or(10),
+        ha_enabled: args.ha.or(manifest.ha_enabled).unwrap_or(false),
+    })
+}
+
 async fn get(args: GetArgs) -> Result<()> {
     let config = Config::load()?;
     let token = config.require_token()?;
-
-    let org_id = match args.org {
-        Some(id) => id,
-        None => config.require_linked_org()?,
-    };
-
     let client = QuomeClient::new(Some(&token), None)?;
 
+    let org_id = args
+        .org
+        .resolve_cached(&config, &client, args.offline)
+        .await?;
+
     let sp = ui::spinner("Fetching database...");
-    let db = client.get_database(org_id, args.id).await?;
+    let db = cache::through(
+        &format!("database-{}", args.id),
+        args.offline,
+        client.get_database(org_id, args.id),
+    )
+    .await?;
     sp.finish_and_clear();
 
-    if args.json {
-        println!("{}", serde_json::to_string_pretty(&db)?);
+    if ui::structured(args.json) {
+        ui::print_structured(&db)?;
     } else {
         let mut details = vec![
             ("ID", db.id.to_string()),
@@ -258,14 +386,8 @@ async fn get(args: GetArgs) -> Result<()> {
         if let Some(ref ip) = db.private_ip {
             details.push(("Private IP", ip.clone()));
         }
-        details.push((
-            "Created",
-            db.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
-        ));
-        details.push((
-            "Updated",
-            db.updated_at.format("%Y-%m-%d %H:%M:%S").to_string(),
-        ));
+        details.push(("Created", ui::fmt_time(&db.created_at, TimeStyle::Detail)));
+        details.push(("Updated", ui::fmt_time(&db.updated_at, TimeStyle::Detail)));
 
         let details_ref: Vec<(&str, &str)> =
             details.iter().map(|(k, v)| (*k, v.as_str())).collect();
@@ -279,14 +401,10 @@ async fn get(args: GetArgs) -> Result<()> {
 async fn update(args: UpdateArgs) -> Result<()> {
     let config = Config::load()?;
     let token = config.require_token()?;
-
-    let org_id = match args.org {
-        Some(id) => id,
-        None => config.require_linked_org()?,
-    };
-
     let client = QuomeClient::new(Some(&token), None)?;
 
+    let org_id = args.org.resolve(&config, &client).await?;
+
     let req = UpdateDatabaseRequest {
         description: args.description,
         tier: args.tier,
@@ -294,12 +412,19 @@ async fn update(args: UpdateArgs) -> Result<()> {
         ha_enabled: args.ha,
     };
 
+    // A description edit is instant; anything else is rolled out
+    let rollout = req.tier.is_some() || req.storage_gb.is_some() || req.ha_enabled.is_some();
+
     let sp = ui::spinner("Updating database...");
-    let db = client.update_database(org_id, args.id, &req).await?;
+    let mut db = client.update_database(org_id, args.id, &req).await?;
     sp.finish_and_clear();
 
-    if args.json {
-        println!("{}", serde_json::to_string_pretty(&db)?);
+    if args.wait {
+        db = wait_until_running(&client, org_id, db, rollout, args.timeout).await?;
+    }
+
+    if ui::structured(args.json) {
+        ui::print_structured(&db)?;
     } else {
         ui::print_success(
             "Updated database",
@@ -310,19 +435,65 @@ async fn update(args: UpdateArgs) -> Result<()> {
     Ok(())
 }
 
+/// Poll until the database settles back into `running`, printing each
+/// status it passes through (`updating`, `pending`, ...).
+///
+/// The update response may still say `running` from before the rollout
+/// started, so for a `rollout` that isn't trusted: the database has to leave
+/// `running` (or show a newer `updated_at`) before `running` counts.
+async fn wait_until_running(
+    client: &QuomeClient,
+    org_id: Uuid,
+    db: Database,
+    rollout: bool,
+    timeout: u64,
+) -> Result<Database> {
+    let sp = ui::spinner(&format!("Waiting for {} ({})...", db.name, db.status));
+    let accepted_at = db.updated_at;
+    let mut started = !rollout || db.status != "running";
+    let mut last = db.status.clone();
+    let db = util::poll_until(
+        util::POLL_INTERVAL,
+        Duration::from_secs(timeout),
+        || client.get_database(org_id, db.id),
+        |db| {
+            if db.status != last {
+                sp.suspend(|| eprintln!("  {} {}", "•".cyan(), status_color(&db.status)));
+                sp.set_message(format!("Waiting for {} ({})...", db.name, db.status));
+                last = db.status.clone();
+            }
+            started |= db.status != "running" || db.updated_at > accepted_at;
+            db.status == "failed" || (started && db.status == "running")
+        },
+    )
+    .await;
+    sp.finish_and_clear();
+
+    let db = db?;
+    if db.status == "failed" {
+        return Err(QuomeError::ApiError(format!(
+            "database {} failed while applying the update",
+            db.name
+        )));
+    }
+    Ok(db)
+}
+
 async fn delete(args: DeleteArgs) -> Result<()> {
     let config = Config::load()?;
     let token = config.require_token()?;
+    let client = QuomeClient::new(Some(&token), None)?;
+
+    let org_id = args.org.resolve(&config, &client).await?;
 
-    let org_id = match args.org {
-        Some(id) => id,
-        None => config.require_linked_org()?,
+    let Some(db_id) = args.id else {
+        return delete_all(args, org_id, &client).await;
     };
 
     if !args.force {
         let confirm = inquire::Confirm::new(&format!(
             "Are you sure you want to delete database {}?",
-            args.id
+            db_id
         ))
         .with_default(false)
         .prompt()
@@ -334,13 +505,86 @@ async fn delete(args: DeleteArgs) -> Result<()> {
         }
     }
 
-    let client = QuomeClient::new(Some(&token), None)?;
-
     let sp = ui::spinner("Deleting database...");
-    client.delete_database(org_id, args.id).await?;
+    client.delete_database(org_id, db_id).await?;
     sp.finish_and_clear();
 
-    ui::print_success("Deleted database", &[("ID", &args.id.to_string())]);
+    ui::print_success("Deleted database", &[("ID", &db_id.to_string())]);
 
     Ok(())
 }
+
+async fn delete_all(args: DeleteArgs, org_id: Uuid, client: &QuomeClient) -> Result<()> {
+    let sp = ui::spinner("Fetching databases...");
+    let response = client.list_databases(org_id).await?;
+    sp.finish_and_clear();
+
+    let dbs = bulk::select(
+        response.data,
+        |db| &db.name,
+        args.filter.as_deref(),
+        args.really_all,
+        "database",
+    )?;
+
+    if dbs.is_empty() {
+        println!("No matching databases found.");
+        return Ok(());
+    }
+
+    let names: Vec<String> = dbs
+        .iter()
+        .map(|db| format!("{} ({})", db.name, db.id))
+        .collect();
+
+    if !bulk::confirm("Delete", "databases", &names, args.force)? {
+        println!("Cancelled.");
+        return Ok(());
+    }
+
+    let items = names.into_iter().zip(dbs.iter().map(|db| db.id)).collect();
+    bulk::run_batch("Deleted", items, args.fail_fast, |id| {
+        client.delete_database(org_id, id)
+    })
+    .await
+    .report(ui::structured(args.json))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{create_request, CreateArgs, DatabaseManifest};
+    use clap::Parser;
+
+    #[test]
+    fn flags_override_manifest_and_defaults_fill_the_rest() {
+        let manifest: DatabaseManifest = serde_yaml::from_str(
+            "name: main\ntier: db-custom-2-8192\nstorage_gb: 50\nha_enabled: true\n",
+        )
+        .unwrap();
+        let args = CreateArgs::parse_from(["create", "--file", "db.yaml", "--storage-gb", "100"]);
+        let req = create_request(&args, manifest).unwrap();
+
+        assert_eq!(req.name, "main");
+        assert_eq!(req.tier, "db-custom-2-8192");
+        assert_eq!(req.storage_gb, 100);
+        assert_eq!(req.version, "17");
+        assert!(req.ha_enabled);
+
+        let args = CreateArgs::parse_from(["create", "main", "--ha=false"]);
+        let manifest = DatabaseManifest {
+            ha_enabled: Some(true),
+            ..DatabaseManifest::default()
+        };
+        assert!(!create_request(&args, manifest).unwrap().ha_enabled);
+        let args = CreateArgs::parse_from(["create", "--ha", "main"]);
+        assert!(
+            create_request(&args, DatabaseManifest::default())
+                .unwrap()
+                .ha_enabled
+        );
+
+        let unnamed = CreateArgs::parse_from(["create", "--file", "db.yaml"]);
+        assert!(create_request(&unnamed, DatabaseManifest::default()).is_err());
+        assert!(serde_yaml::from_str::<DatabaseManifest>("vcpu: 2\n").is_err());
+    }
+}
@@ -1,12 +1,16 @@
-use clap::{Parser, Subcommand};
+use clap::{ArgGroup, Parser, Subcommand};
 use colored::Colorize;
+use std::time::Duration;
 use uuid::Uuid;
 
-use crate::api::models::{CreateDeploymentRequest, DeploymentStatus};
+use crate::api::models::{CreateDeploymentRequest, Deployment, DeploymentStatus};
+use crate::cache;
 use crate::client::QuomeClient;
 use crate::config::Config;
-use crate::errors::Result;
-use crate::ui::{self, DeploymentRow};
+use crate::context::{self, AppArgs, OrgArgs};
+use crate::errors::{QuomeError, Result};
+use crate::ui::{self, DeploymentRow, TimeStyle};
+use crate::util;
 
 #[derive(Subcommand)]
 pub enum DeploymentsCommands {
@@ -16,17 +20,23 @@ pub enum DeploymentsCommands {
     Get(GetArgs),
     /// Trigger a new deployment
     Create(CreateArgs),
+    /// Stream a deployment's events until it finishes
+    Watch(WatchArgs),
+    /// Redeploy another app's last successful commit (e.g. staging → production)
+    Promote(PromoteArgs),
 }
 
 #[derive(Parser)]
 pub struct ListArgs {
-    /// Application ID (uses linked app if not provided)
-    #[arg(long)]
-    app: Option<Uuid>,
+    #[command(flatten)]
+    app: AppArgs,
+
+    #[command(flatten)]
+    org: OrgArgs,
 
-    /// Organization ID (uses linked org if not provided)
+    /// Serve the last cached result instead of calling the API
     #[arg(long)]
-    org: Option<Uuid>,
+    offline: bool,
 
     /// Output as JSON
     #[arg(long)]
@@ -38,17 +48,23 @@ pub struct GetArgs {
     /// Deployment ID
     id: Uuid,
 
-    /// Application ID (uses linked app if not provided)
-    #[arg(long)]
-    app: Option<Uuid>,
+    #[command(flatten)]
+    app: AppArgs,
 
-    /// Organization ID (uses linked org if not provided)
+    #[command(flatten)]
+    org: OrgArgs,
+
+    /// Serve the last cached result instead of calling the API
     #[arg(long)]
-    org: Option<Uuid>,
+    offline: bool,
 
     /// Output as JSON
     #[arg(long)]
     json: bool,
+
+    /// Print only the deployment's events, as a JSON array
+    #[arg(long, conflicts_with = "json")]
+    events_json: bool,
 }
 
 #[derive(Parser)]
@@ -57,13 +73,67 @@ pub struct CreateArgs {
     #[arg(long)]
     branch: Option<String>,
 
-    /// Application ID (uses linked app if not provided)
+    #[command(flatten)]
+    app: AppArgs,
+
+    #[command(flatten)]
+    org: OrgArgs,
+
+    /// Output as JSON
     #[arg(long)]
-    app: Option<Uuid>,
+    json: bool,
+}
+
+#[derive(Parser)]
+pub struct WatchArgs {
+    /// Deployment ID [default: the in-progress deployment, else the newest]
+    id: Option<Uuid>,
+
+    #[command(flatten)]
+    app: AppArgs,
 
-    /// Organization ID (uses linked org if not provided)
+    #[command(flatten)]
+    org: OrgArgs,
+
+    /// Give up after this many seconds
+    #[arg(long, default_value_t = 1800)]
+    timeout: u64,
+
+    /// Print only the finished deployment, as JSON
     #[arg(long)]
-    org: Option<Uuid>,
+    json: bool,
+}
+
+#[derive(Parser)]
+#[command(group(ArgGroup::new("from").required(true).args(["from_app", "from_app_name"])))]
+#[command(group(ArgGroup::new("to").required(true).args(["to_app", "to_app_name"])))]
+pub struct PromoteArgs {
+    /// Application to promote from
+    #[arg(long)]
+    from_app: Option<Uuid>,
+
+    /// Application to promote from, by name or slug
+    #[arg(long)]
+    from_app_name: Option<String>,
+
+    /// Application to deploy to
+    #[arg(long)]
+    to_app: Option<Uuid>,
+
+    /// Application to deploy to, by name or slug
+    #[arg(long)]
+    to_app_name: Option<String>,
+
+    /// Deployment of --from-app to promote [default: its newest successful one]
+    #[arg(long)]
+    deployment: Option<Uuid>,
+
+    #[command(flatten)]
+    org: OrgArgs,
+
+    /// Skip confirmation prompt
+    #[arg(short, long)]
+    force: bool,
 
     /// Output as JSON
     #[arg(long)]
@@ -75,10 +145,12 @@ pub async fn execute(command: DeploymentsCommands) -> Result<()> {
         DeploymentsCommands::List(args) => list(args).await,
         DeploymentsCommands::Get(args) => get(args).await,
         DeploymentsCommands::Create(args) => create(args).await,
+        DeploymentsCommands::Watch(args) => watch(args).await,
+        DeploymentsCommands::Promote(args) => promote(args).await,
     }
 }
 
-fn status_color(status: &DeploymentStatus) -> colored::ColoredString {
+pub(crate) fn status_color(status: &DeploymentStatus) -> colored::ColoredString {
     match status {
         DeploymentStatus::Created => "created".yellow(),
         DeploymentStatus::InProgress => "in_progress".blue(),
@@ -91,39 +163,40 @@ fn status_color(status: &DeploymentStatus) -> colored::ColoredString {
 async fn list(args: ListArgs) -> Result<()> {
     let config = Config::load()?;
     let token = config.require_token()?;
+    let client = QuomeClient::new(Some(&token), None)?;
 
-    let org_id = match args.org {
-        Some(id) => id,
-        None => config.require_linked_org()?,
-    };
-
-    let app_id = match args.app {
-        Some(id) => id,
-        None => config.require_linked_app()?,
-    };
+    let org_id = args
+        .org
+        .resolve_cached(&config, &client, args.offline)
+        .await?;
 
-    let client = QuomeClient::new(Some(&token), None)?;
+    let app_id = args
+        .app
+        .resolve_cached(&config, &client, org_id, args.offline)
+        .await?;
 
     let sp = ui::spinner("Fetching deployments...");
-    let response = client.list_deployments(org_id, app_id).await?;
+    let deployments = cache::through(&format!("deployments-{}", app_id), args.offline, async {
+        Ok(client.list_deployments(org_id, app_id).await?.data)
+    })
+    .await?;
     sp.finish_and_clear();
 
-    if args.json {
-        println!("{}", serde_json::to_string_pretty(&response.data)?);
+    if ui::structured(args.json) {
+        ui::print_structured(&deployments)?;
     } else {
-        if response.data.is_empty() {
+        if deployments.is_empty() {
             println!("No deployments found.");
             return Ok(());
         }
 
-        let rows: Vec<DeploymentRow> = response
-            .data
+        let rows: Vec<DeploymentRow> = deployments
             .iter()
             .map(|d| DeploymentRow {
                 id: d.id.to_string(),
                 status: status_color(&d.status).to_string(),
                 branch: d.branch.clone().unwrap_or_else(|| "-".to_string()),
-                created: d.created_at.format("%Y-%m-%d %H:%M").to_string(),
+                created: ui::fmt_time(&d.created_at, TimeStyle::List),
             })
             .collect();
 
@@ -136,25 +209,31 @@ async fn list(args: ListArgs) -> Result<()> {
 async fn get(args: GetArgs) -> Result<()> {
     let config = Config::load()?;
     let token = config.require_token()?;
+    let client = QuomeClient::new(Some(&token), None)?;
 
-    let org_id = match args.org {
-        Some(id) => id,
-        None => config.require_linked_org()?,
-    };
-
-    let app_id = match args.app {
-        Some(id) => id,
-        None => config.require_linked_app()?,
-    };
+    let org_id = args
+        .org
+        .resolve_cached(&config, &client, args.offline)
+        .await?;
 
-    let client = QuomeClient::new(Some(&token), None)?;
+    let app_id = args
+        .app
+        .resolve_cached(&config, &client, org_id, args.offline)
+        .await?;
 
     let sp = ui::spinner("Fetching deployment...");
-    let deployment = client.get_deployment(org_id, app_id, args.id).await?;
+    let deployment = cache::through(
+        &format!("deployment-{}", args.id),
+        args.offline,
+        client.get_deployment(org_id, app_id, args.id),
+    )
+    .await?;
     sp.finish_and_clear();
 
-    if args.json {
-        println!("{}", serde_json::to_string_pretty(&deployment)?);
+    if args.events_json {
+        ui::print_structured(&deployment.events)?;
+    } else if ui::structured(args.json) {
+        ui::print_structured(&deployment)?;
     } else {
         let status_str = status_color(&deployment.status).to_string();
         let mut details = vec![
@@ -162,10 +241,7 @@ async fn get(args: GetArgs) -> Result<()> {
             ("Status", status_str),
             (
                 "Created",
-                deployment
-                    .created_at
-                    .format("%Y-%m-%d %H:%M:%S")
-                    .to_string(),
+                ui::fmt_time(&deployment.created_at, TimeStyle::Detail),
             ),
         ];
 
@@ -190,7 +266,7 @@ async fn get(args: GetArgs) -> Result<()> {
             for event in &deployment.events {
                 println!(
                     "  {} {} {}",
-                    event.created_at.format("%H:%M:%S").to_string().dimmed(),
+                    ui::fmt_time(&event.created_at, TimeStyle::Clock).dimmed(),
                     "•".cyan(),
                     event.message
                 );
@@ -204,18 +280,11 @@ async fn get(args: GetArgs) -> Result<()> {
 async fn create(args: CreateArgs) -> Result<()> {
     let config = Config::load()?;
     let token = config.require_token()?;
+    let client = QuomeClient::new(Some(&token), None)?;
 
-    let org_id = match args.org {
-        Some(id) => id,
-        None => config.require_linked_org()?,
-    };
-
-    let app_id = match args.app {
-        Some(id) => id,
-        None => config.require_linked_app()?,
-    };
+    let org_id = args.org.resolve(&config, &client).await?;
 
-    let client = QuomeClient::new(Some(&token), None)?;
+    let app_id = args.app.resolve(&config, &client, org_id).await?;
 
     let sp = ui::spinner("Triggering deployment...");
     let deployment = client
@@ -230,8 +299,8 @@ async fn create(args: CreateArgs) -> Result<()> {
         .await?;
     sp.finish_and_clear();
 
-    if args.json {
-        println!("{}", serde_json::to_string_pretty(&deployment)?);
+    if ui::structured(args.json) {
+        ui::print_structured(&deployment)?;
     } else {
         ui::print_success(
             "Deployment triggered",
@@ -244,3 +313,290 @@ async fn create(args: CreateArgs) -> Result<()> {
 
     Ok(())
 }
+
+async fn watch(args: WatchArgs) -> Result<()> {
+    let config = Config::load()?;
+    let token = config.require_token()?;
+    let client = QuomeClient::new(Some(&token), None)?;
+
+    let org_id = args.org.resolve(&config, &client).await?;
+
+    let app_id = args.app.resolve(&config, &client, org_id).await?;
+
+    let deployment_id = match args.id {
+        Some(id) => id,
+        None => {
+            let sp = ui::spinner("Finding latest deployment...");
+            let deployments = client.list_deployments(org_id, app_id).await?.data;
+            sp.finish_and_clear();
+            pick_latest(&deployments)
+                .ok_or_else(|| QuomeError::NotFound("this app has no deployments".into()))?
+                .id
+        }
+    };
+
+    let json = ui::structured(args.json);
+    let deployment = follow(
+        &client,
+        org_id,
+        app_id,
+        deployment_id,
+        Duration::from_secs(args.timeout),
+        json,
+    )
+    .await?;
+
+    if json {
+        ui::print_structured(&deployment)?;
+    }
+    succeeded(&deployment, json)
+}
+
+/// Poll a deployment until it reaches a terminal status, printing each status
+/// change and every new event as it arrives unless `quiet`.
+pub(crate) async fn follow(
+    client: &QuomeClient,
+    org_id: Uuid,
+    app_id: Uuid,
+    deployment_id: Uuid,
+    timeout: Duration,
+    quiet: bool,
+) -> Result<Deployment> {
+    let sp = ui::spinner("Watching deployment...");
+    let mut printed = 0;
+    let mut last: Option<DeploymentStatus> = None;
+    let deployment = util::poll_until(
+        util::POLL_INTERVAL,
+        timeout,
+        || client.get_deployment(org_id, app_id, deployment_id),
+        |d| {
+            if !quiet {
+                sp.suspend(|| {
+                    if last.as_ref() != Some(&d.status) {
+                        println!("{} {}", "Status:".bold(), status_color(&d.status));
+                    }
+                    for event in d.events.iter().skip(printed) {
+                        println!(
+                            "  {} {} {}",
+                            ui::fmt_time(&event.created_at, TimeStyle::Clock).dimmed(),
+                            "•".cyan(),
+                            event.message
+                        );
+                    }
+                });
+                printed = printed.max(d.events.len());
+                last = Some(d.status.clone());
+            }
+            sp.set_message(format!("Deployment {}... Ctrl-C to stop", d.status));
+            d.status.is_terminal()
+        },
+    )
+    .await;
+    sp.finish_and_clear();
+    deployment
+}
+
+/// `Ok` for a successful deployment (announced unless `quiet`); otherwise an
+/// error carrying the final status and failure reason.
+pub(crate) fn succeeded(deployment: &Deployment, quiet: bool) -> Result<()> {
+    match deployment.status {
+        DeploymentStatus::Success => {
+            if !quiet {
+                println!();
+                println!("{} Deployment {} succeeded", "✓".green(), deployment.id);
+            }
+            Ok(())
+        }
+        ref status => Err(QuomeError::ApiError(format!(
+            "deployment {} {}{}",
+            deployment.id,
+            status,
+            deployment
+                .failure_reason
+                .as_ref()
+                .map(|r| format!(": {}", r))
+                .unwrap_or_default()
+        ))),
+    }
+}
+
+/// Redeploy `--from-app`'s successful deployment (commit and branch) to `--to-app`.
+async fn promote(args: PromoteArgs) -> Result<()> {
+    let config = Config::load()?;
+    let token = config.require_token()?;
+    let client = QuomeClient::new(Some(&token), None)?;
+
+    let org_id = args.org.resolve(&config, &client).await?;
+
+    let from_app = context::resolve_app(
+        &config,
+        &client,
+        org_id,
+        args.from_app,
+        args.from_app_name.as_deref(),
+    )
+    .await?;
+    let to_app = context::resolve_app(
+        &config,
+        &client,
+        org_id,
+        args.to_app,
+        args.to_app_name.as_deref(),
+    )
+    .await?;
+    if from_app == to_app {
+        return Err(QuomeError::ApiError(
+            "the source and target are the same application".into(),
+        ));
+    }
+
+    let sp = ui::spinner("Fetching applications...");
+    let fetched = futures::try_join!(
+        client.get_app(org_id, from_app),
+        client.get_app(org_id, to_app),
+        client.list_deployments(org_id, from_app),
+    );
+    sp.finish_and_clear();
+    let (from, to, deployments) = fetched?;
+
+    let source = match args.deployment {
+        Some(id) => {
+            let d = deployments
+                .data
+                .iter()
+                .find(|d| d.id == id)
+                .ok_or_else(|| {
+                    QuomeError::NotFound(format!("Deployment {} of {}", id, from.name))
+                })?;
+            if d.status != DeploymentStatus::Success {
+                return Err(QuomeError::ApiError(format!(
+                    "Deployment {} is {}; only successful deployments can be promoted",
+                    id, d.status
+                )));
+            }
+            d
+        }
+        None => pick_promotable(&deployments.data).ok_or_else(|| {
+            QuomeError::NotFound(format!("A successful deployment of {}", from.name))
+        })?,
+    };
+    // Deployments can only be triggered from git, so an image-only source
+    // has nothing the target could rebuild
+    let Some(sha) = source.git_commit_sha.clone() else {
+        return Err(QuomeError::ApiError(format!(
+            "Deployment {} has no git commit to promote (only git-sourced apps can be promoted)",
+            source.id
+        )));
+    };
+
+    if !args.force {
+        let confirm = inquire::Confirm::new(&format!(
+            "Deploy {} from {} to {}?",
+            short_sha(&sha),
+            from.name,
+            to.name
+        ))
+        .with_default(false)
+        .prompt()
+        .map_err(|e| QuomeError::Io(std::io::Error::other(e.to_string())))?;
+
+        if !confirm {
+            println!("Cancelled.");
+            return Ok(());
+        }
+    }
+
+    let sp = ui::spinner("Triggering deployment...");
+    let deployment = client
+        .create_deployment(
+            org_id,
+            to.id,
+            &CreateDeploymentRequest {
+                branch: source.branch.clone(),
+                git_commit_sha: Some(sha.clone()),
+            },
+        )
+        .await?;
+    sp.finish_and_clear();
+
+    if ui::structured(args.json) {
+        ui::print_structured(&deployment)?;
+    } else {
+        ui::print_success(
+            "Promotion triggered",
+            &[
+                ("From", &format!("{} ({})", from.name, source.id)),
+                ("To", &to.name),
+                ("Commit", short_sha(&sha)),
+                ("ID", &deployment.id.to_string()),
+                ("Status", &deployment.status.to_string()),
+            ],
+        );
+    }
+
+    Ok(())
+}
+
+fn short_sha(sha: &str) -> &str {
+    sha.get(..7).unwrap_or(sha)
+}
+
+/// The newest successful deployment: what's currently serving on the source.
+fn pick_promotable(deployments: &[Deployment]) -> Option<&Deployment> {
+    deployments
+        .iter()
+        .filter(|d| d.status == DeploymentStatus::Success)
+        .max_by_key(|d| d.created_at)
+}
+
+/// The deployment a bare `watch` means: the newest one still running, or
+/// failing that the newest overall.
+fn pick_latest(deployments: &[Deployment]) -> Option<&Deployment> {
+    deployments
+        .iter()
+        .filter(|d| !d.status.is_terminal())
+        .max_by_key(|d| d.created_at)
+        .or_else(|| deployments.iter().max_by_key(|d| d.created_at))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{pick_latest, pick_promotable};
+    use crate::api::models::Deployment;
+
+    fn deployment(n: u8, status: &str, created_at: &str) -> Deployment {
+        serde_json::from_value(serde_json::json!({
+            "id": format!("00000000-0000-4000-8000-00000000000{}", n),
+            "app_id": "00000000-0000-4000-8000-000000000000",
+            "status": status,
+            "created_at": created_at,
+            "updated_at": created_at,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn pick_latest_prefers_unfinished() {
+        let older_running = deployment(1, "in_progress", "2026-07-01T10:00:00Z");
+        let newest_failed = deployment(2, "failed", "2026-07-01T11:00:00Z");
+        let oldest = deployment(3, "success", "2026-07-01T09:00:00Z");
+
+        let all = vec![oldest.clone(), older_running.clone(), newest_failed.clone()];
+        assert_eq!(pick_latest(&all).unwrap().id, older_running.id);
+
+        let finished = vec![oldest, newest_failed.clone()];
+        assert_eq!(pick_latest(&finished).unwrap().id, newest_failed.id);
+        assert!(pick_latest(&[]).is_none());
+    }
+
+    #[test]
+    fn pick_promotable_takes_newest_success() {
+        let old_success = deployment(1, "success", "2026-07-01T09:00:00Z");
+        let new_success = deployment(2, "success", "2026-07-01T10:00:00Z");
+        let newest_failed = deployment(3, "failed", "2026-07-01T11:00:00Z");
+
+        let all = vec![new_success.clone(), newest_failed.clone(), old_success];
+        assert_eq!(pick_promotable(&all).unwrap().id, new_success.id);
+        assert!(pick_promotable(&[newest_failed]).is_none());
+    }
+}
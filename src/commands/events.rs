@@ -1,68 +1,553 @@
-use clap::Parser;
+use chrono::{DateTime, Utc};
+use clap::{Parser, Subcommand, ValueEnum};
+use colored::Colorize;
+use indicatif::ProgressBar;
+use std::fs::File;
+use std::io::Write;
+use std::path::PathBuf;
 use uuid::Uuid;
 
+use crate::api::models::AuditLog;
 use crate::client::QuomeClient;
 use crate::config::Config;
-use crate::errors::Result;
-use crate::ui::{self, EventRow};
+use crate::context::OrgArgs;
+use crate::errors::{QuomeError, Result};
+use crate::ui::{self, EventRow, TimeStyle};
+use crate::util;
+
+/// Largest page the audit endpoint serves.
+const EXPORT_PAGE_SIZE: u32 = 100;
+
+/// Pages a filtered listing reads before giving up on finding `-n` matches,
+/// so a filter that matches little doesn't walk the whole history.
+const FILTERED_PAGE_CAP: usize = 20;
 
 #[derive(Parser)]
+#[command(args_conflicts_with_subcommands = true)]
 pub struct Args {
-    /// Organization ID (uses linked org if not provided)
-    #[arg(long)]
-    org: Option<Uuid>,
+    #[command(subcommand)]
+    command: Option<EventsCommands>,
+
+    #[command(flatten)]
+    org: OrgArgs,
 
-    /// Number of events to fetch (max 100)
+    /// Number of events to fetch (pages through the history past 100)
     #[arg(short = 'n', long, default_value = "50")]
     limit: u32,
 
+    /// Only events with this action, e.g. app.deleted
+    #[arg(long = "type", value_name = "ACTION")]
+    action: Option<String>,
+
+    /// Only events about this kind of resource, e.g. app
+    #[arg(long, value_name = "TYPE")]
+    resource_type: Option<String>,
+
+    /// Only events by the org member with this email
+    #[arg(long, value_name = "EMAIL")]
+    actor: Option<String>,
+
     /// Output as JSON
     #[arg(long)]
     json: bool,
+
+    /// Write the full audit history to a file instead of listing recent events
+    #[arg(long, value_name = "FORMAT", conflicts_with = "json")]
+    export: Option<ExportFormat>,
+
+    /// File to export to [default: audit-events.csv or audit-events.json]
+    #[arg(long, value_name = "PATH", requires = "export")]
+    file: Option<PathBuf>,
+
+    /// Overwrite the export file if it already exists
+    #[arg(short, long, requires = "export")]
+    force: bool,
+
+    /// Only export events at or after this date (YYYY-MM-DD, RFC 3339, or a duration ago like 7d)
+    #[arg(long, value_name = "DATE", requires = "export")]
+    since: Option<String>,
+
+    /// Only export events before this date (same formats as --since)
+    #[arg(long, value_name = "DATE", requires = "export")]
+    until: Option<String>,
+}
+
+#[derive(Subcommand)]
+pub enum EventsCommands {
+    /// Show one event with its full details
+    Get(GetArgs),
+}
+
+#[derive(Parser)]
+pub struct GetArgs {
+    /// Event ID (the `id` field of `quome events --json`)
+    id: String,
+
+    #[command(flatten)]
+    org: OrgArgs,
+
+    /// How far back to search (YYYY-MM-DD, RFC 3339, or a duration ago like 30d)
+    #[arg(long, value_name = "DATE", default_value = "90d")]
+    since: String,
+
+    /// Output as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+/// `--type`, `--resource-type` and `--actor`, all of which must match.
+/// The audit endpoint can't filter, so this runs over fetched pages.
+#[derive(Default)]
+struct Filter {
+    action: Option<String>,
+    resource_type: Option<String>,
+    actor: Option<Uuid>,
+}
+
+impl Filter {
+    fn is_empty(&self) -> bool {
+        self.action.is_none() && self.resource_type.is_none() && self.actor.is_none()
+    }
+
+    fn matches(&self, event: &AuditLog) -> bool {
+        let same = |want: &Option<String>, got: Option<&str>| {
+            want.iter()
+                .all(|w| got.is_some_and(|g| g.eq_ignore_ascii_case(w)))
+        };
+        same(&self.action, Some(&event.action))
+            && same(&self.resource_type, event.resource_type.as_deref())
+            && self.actor.iter().all(|a| event.user_id == Some(*a))
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum ExportFormat {
+    Csv,
+    Json,
 }
 
 pub async fn execute(args: Args) -> Result<()> {
+    if let Some(EventsCommands::Get(args)) = args.command {
+        return get(args).await;
+    }
+
     let config = Config::load()?;
     let token = config.require_token()?;
+    let client = QuomeClient::new(Some(&token), None)?;
 
-    let org_id = match args.org {
-        Some(id) => id,
-        None => config.require_linked_org()?,
+    let org_id = args.org.resolve(&config, &client).await?;
+    let filter = Filter {
+        action: args.action.clone(),
+        resource_type: args.resource_type.clone(),
+        actor: match args.actor {
+            Some(ref email) => Some(actor_id(&client, org_id, email).await?),
+            None => None,
+        },
     };
 
-    let client = QuomeClient::new(Some(&token), None)?;
+    if let Some(format) = args.export {
+        return export(&client, org_id, format, &filter, &args).await;
+    }
 
     let sp = ui::spinner("Fetching audit events...");
-    let response = client.list_audit_logs(org_id, Some(args.limit)).await?;
+    let events = if args.limit <= EXPORT_PAGE_SIZE && filter.is_empty() {
+        client
+            .list_audit_logs(org_id, Some(args.limit), 1)
+            .await
+            .map(|response| response.items)
+    } else {
+        fetch_recent(&client, org_id, args.limit as usize, &filter, &sp).await
+    };
     sp.finish_and_clear();
+    let events = events?;
 
-    if args.json {
-        println!("{}", serde_json::to_string_pretty(&response.items)?);
+    if ui::structured(args.json) {
+        ui::print_structured(&events)?;
     } else {
-        if response.items.is_empty() {
+        if events.is_empty() {
             println!("No events found.");
             return Ok(());
         }
 
-        let rows: Vec<EventRow> = response
-            .items
-            .iter()
-            .map(|event| {
-                let resource = match (&event.resource_type, &event.resource_id) {
-                    (Some(rt), Some(rid)) => format!("{} ({})", rid, rt),
-                    (Some(rt), None) => rt.clone(),
-                    _ => "-".to_string(),
-                };
-                EventRow {
-                    time: event.created_at.format("%Y-%m-%d %H:%M").to_string(),
-                    action: event.action.clone(),
-                    resource,
-                }
-            })
-            .collect();
+        ui::print_table(event_rows(&events));
+    }
+
+    Ok(())
+}
+
+/// The user ID behind `--actor`, looked up among the org's members. Events
+/// by people who have since left the org can't be matched this way.
+async fn actor_id(client: &QuomeClient, org_id: Uuid, email: &str) -> Result<Uuid> {
+    let sp = ui::spinner("Fetching members...");
+    let members = client.list_org_members(org_id).await;
+    sp.finish_and_clear();
+    members?
+        .into_iter()
+        .find(|m| m.user_email.eq_ignore_ascii_case(email))
+        .map(|m| m.user_id)
+        .ok_or_else(|| QuomeError::NotFound(format!("Member '{}'", email)))
+}
+
+async fn get(args: GetArgs) -> Result<()> {
+    let since = util::parse_date(&args.since)?;
+
+    let config = Config::load()?;
+    let token = config.require_token()?;
+    let client = QuomeClient::new(Some(&token), None)?;
+
+    let org_id = args.org.resolve(&config, &client).await?;
+
+    // The audit endpoint has no single-event route, so walk the trail back
+    // as far as --since
+    let sp = ui::spinner("Finding event...");
+    let mut found = None;
+    let walked = walk(&client, org_id, &sp, |items| {
+        let past_since = items.last().is_some_and(|e| e.created_at < since);
+        found = items.into_iter().find(|e| e.id == args.id);
+        found.is_none() && !past_since
+    })
+    .await;
+    sp.finish_and_clear();
+    walked?;
+    let event = found.ok_or_else(|| {
+        QuomeError::NotFound(format!(
+            "event '{}' since {}; pass an earlier --since to search further back",
+            args.id,
+            ui::fmt_time(&since, TimeStyle::List)
+        ))
+    })?;
+
+    if ui::structured(args.json) {
+        ui::print_structured(&event)?;
+        return Ok(());
+    }
+
+    let resource = match (&event.resource_type, &event.resource_id) {
+        (Some(rt), Some(rid)) => format!("{} ({})", rid, rt),
+        (Some(rt), None) => rt.clone(),
+        (None, Some(rid)) => rid.clone(),
+        (None, None) => "-".to_string(),
+    };
+    let or_dash = |v: Option<String>| v.unwrap_or_else(|| "-".to_string());
+    let details = [
+        ("ID", event.id.clone()),
+        ("Time", ui::fmt_time(&event.created_at, TimeStyle::Detail)),
+        ("Action", event.action.clone()),
+        ("Actor", or_dash(event.user_id.map(|u| u.to_string()))),
+        ("Resource", resource),
+        ("IP address", or_dash(event.ip_address.clone())),
+    ];
+    let details_ref: Vec<(&str, &str)> = details.iter().map(|(k, v)| (*k, v.as_str())).collect();
+    ui::print_detail(&event.action, &details_ref);
+
+    println!();
+    println!("{}", "Details".bold());
+    match event.details {
+        Some(ref details) if !details.is_null() => {
+            println!("{}", serde_json::to_string_pretty(details)?)
+        }
+        _ => println!("  No details recorded."),
+    }
+
+    Ok(())
+}
+
+pub(crate) fn event_rows(events: &[AuditLog]) -> Vec<EventRow> {
+    events
+        .iter()
+        .map(|event| {
+            let resource = match (&event.resource_type, &event.resource_id) {
+                (Some(rt), Some(rid)) => format!("{} ({})", rid, rt),
+                (Some(rt), None) => rt.clone(),
+                _ => "-".to_string(),
+            };
+            EventRow {
+                time: ui::fmt_time(&event.created_at, TimeStyle::List),
+                action: event.action.clone(),
+                resource,
+            }
+        })
+        .collect()
+}
+
+/// Events about one resource among the org's most recent page of events.
+/// The audit endpoint can't filter by resource, so older history needs
+/// `quome events --export`.
+pub(crate) async fn recent_for_resource(
+    client: &QuomeClient,
+    org_id: Uuid,
+    resource_id: &str,
+) -> Result<Vec<AuditLog>> {
+    let response = client
+        .list_audit_logs(org_id, Some(EXPORT_PAGE_SIZE), 1)
+        .await?;
+    Ok(response
+        .items
+        .into_iter()
+        .filter(|e| e.resource_id.as_deref() == Some(resource_id))
+        .collect())
+}
+
+async fn export(
+    client: &QuomeClient,
+    org_id: Uuid,
+    format: ExportFormat,
+    filter: &Filter,
+    args: &Args,
+) -> Result<()> {
+    let since = args.since.as_deref().map(util::parse_date).transpose()?;
+    let until = args.until.as_deref().map(util::parse_date).transpose()?;
+    let path = args.file.clone().unwrap_or_else(|| {
+        PathBuf::from(match format {
+            ExportFormat::Csv => "audit-events.csv",
+            ExportFormat::Json => "audit-events.json",
+        })
+    });
+    let exists = || {
+        QuomeError::ApiError(format!(
+            "{} already exists. Pass --force to overwrite it.",
+            path.display()
+        ))
+    };
+
+    // Fast path so a refusal doesn't walk the whole history first; opening
+    // with create_new below is what actually keeps the file from being clobbered
+    if !args.force && path.symlink_metadata().is_ok() {
+        return Err(exists());
+    }
+
+    let sp = ui::spinner("Fetching audit events...");
+    let events = fetch_range(client, org_id, since, until, filter, &sp).await;
+    sp.finish_and_clear();
+    let events = events?;
+
+    let contents = match format {
+        ExportFormat::Csv => to_csv(&events)?,
+        ExportFormat::Json => serde_json::to_string_pretty(&events)? + "\n",
+    };
+    let mut file = if args.force {
+        File::create(&path)?
+    } else {
+        File::options()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::AlreadyExists => exists(),
+                _ => e.into(),
+            })?
+    };
+    file.write_all(contents.as_bytes())?;
+
+    println!(
+        "{} Exported {} events to {}",
+        "Success!".green().bold(),
+        events.len(),
+        path.display()
+    );
+    Ok(())
+}
+
+/// Keep the events in `[since, until)`, walking back only as far as `since`.
+async fn fetch_range(
+    client: &QuomeClient,
+    org_id: Uuid,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+    filter: &Filter,
+    sp: &ProgressBar,
+) -> Result<Vec<AuditLog>> {
+    let mut events = Vec::new();
+    walk(client, org_id, sp, |items| {
+        let past_since = since.is_some_and(|s| items.last().is_some_and(|e| e.created_at < s));
+        events.extend(items.into_iter().filter(|e| {
+            since.iter().all(|s| e.created_at >= *s)
+                && until.iter().all(|u| e.created_at < *u)
+                && filter.matches(e)
+        }));
+        !past_since
+    })
+    .await?;
+    Ok(events)
+}
+
+/// The newest `limit` events matching `filter`, gathered across as many
+/// pages as it takes, or [`FILTERED_PAGE_CAP`] pages with a filter.
+async fn fetch_recent(
+    client: &QuomeClient,
+    org_id: Uuid,
+    limit: usize,
+    filter: &Filter,
+    sp: &ProgressBar,
+) -> Result<Vec<AuditLog>> {
+    let mut events = Vec::new();
+    // Pages can repeat rows when events land mid-walk; only new ids count
+    let mut ids = std::collections::HashSet::new();
+    let mut pages = 0;
+    let mut capped = false;
+    walk(client, org_id, sp, |items| {
+        pages += 1;
+        for event in items.into_iter().filter(|e| filter.matches(e)) {
+            if ids.insert(event.id.clone()) {
+                events.push(event);
+            }
+        }
+        capped = !filter.is_empty() && pages >= FILTERED_PAGE_CAP && ids.len() < limit;
+        ids.len() < limit && !capped
+    })
+    .await?;
+    if capped {
+        sp.suspend(|| {
+            eprintln!(
+                "{} stopped after the newest {} events with {} matching; `--export` with the same filters searches the full history",
+                "note:".yellow().bold(),
+                FILTERED_PAGE_CAP * EXPORT_PAGE_SIZE as usize,
+                ids.len()
+            )
+        });
+    }
+    Ok(newest_first(events, limit))
+}
+
+/// Sort events newest first, drop any that appear twice (pages shift when
+/// events land mid-walk) and keep the first `limit`.
+fn newest_first(mut events: Vec<AuditLog>, limit: usize) -> Vec<AuditLog> {
+    events.sort_by_key(|e| std::cmp::Reverse(e.created_at));
+    let mut seen = std::collections::HashSet::new();
+    events.retain(|e| seen.insert(e.id.clone()));
+    events.truncate(limit);
+    events
+}
+
+/// Walk the audit trail page by page (newest first), handing each page to
+/// `visit` until it returns `false`. Also stops at the last page, or if the
+/// server hands back a page it already sent.
+async fn walk(
+    client: &QuomeClient,
+    org_id: Uuid,
+    sp: &ProgressBar,
+    mut visit: impl FnMut(Vec<AuditLog>) -> bool,
+) -> Result<()> {
+    let mut scanned = 0;
+    let mut previous_first: Option<String> = None;
+
+    for page in 1.. {
+        let response = client
+            .list_audit_logs(org_id, Some(EXPORT_PAGE_SIZE), page)
+            .await?;
+        let first = response.items.first().map(|e| e.id.clone());
+        if first.is_none() || first == previous_first {
+            break;
+        }
+        previous_first = first;
+
+        let full_page = response.items.len() as u32 >= EXPORT_PAGE_SIZE;
+        scanned += response.items.len();
+        let more = visit(response.items);
 
-        ui::print_table(rows);
+        sp.set_message(match response.total {
+            Some(total) => format!("Fetching audit events... {}/{}", scanned, total),
+            None => format!("Fetching audit events... {}", scanned),
+        });
+        let seen_all = response.total.is_some_and(|t| scanned as i64 >= t);
+        if !full_page || !more || seen_all {
+            break;
+        }
     }
 
     Ok(())
 }
+
+fn to_csv(events: &[AuditLog]) -> Result<String> {
+    let mut out = String::from(
+        "id,created_at,action,user_id,organization_id,resource_type,resource_id,ip_address,details\n",
+    );
+    for e in events {
+        let details = match &e.details {
+            Some(d) => serde_json::to_string(d)?,
+            None => String::new(),
+        };
+        let fields = [
+            e.id.clone(),
+            e.created_at.to_rfc3339(),
+            e.action.clone(),
+            e.user_id.map(|u| u.to_string()).unwrap_or_default(),
+            e.organization_id.map(|o| o.to_string()).unwrap_or_default(),
+            e.resource_type.clone().unwrap_or_default(),
+            e.resource_id.clone().unwrap_or_default(),
+            e.ip_address.clone().unwrap_or_default(),
+            details,
+        ];
+        let row: Vec<String> = fields.iter().map(|f| csv_field(f)).collect();
+        out.push_str(&row.join(","));
+        out.push('\n');
+    }
+    Ok(out)
+}
+
+/// Quote a CSV field when it contains a delimiter, quote or line break.
+fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{csv_field, newest_first, Filter};
+    use crate::api::models::AuditLog;
+    use chrono::{Duration, Utc};
+
+    fn event(id: &str, minutes_ago: i64) -> AuditLog {
+        serde_json::from_value(serde_json::json!({
+            "id": id,
+            "action": "app.updated",
+            "created_at": Utc::now() - Duration::minutes(minutes_ago),
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn filters_combine_and_ignore_case() {
+        let mut deleted = event("a", 1);
+        deleted.action = "app.deleted".into();
+        deleted.resource_type = Some("app".into());
+        let updated = event("b", 2);
+
+        let filter = Filter {
+            action: Some("App.Deleted".into()),
+            resource_type: Some("APP".into()),
+            actor: None,
+        };
+        assert!(filter.matches(&deleted));
+        assert!(!filter.matches(&updated));
+
+        let by_someone = Filter {
+            actor: Some(uuid::Uuid::nil()),
+            ..filter
+        };
+        assert!(!by_someone.matches(&deleted));
+        assert!(Filter::default().matches(&updated));
+    }
+
+    #[test]
+    fn newest_first_sorts_dedupes_and_truncates() {
+        let events = vec![event("b", 2), event("a", 1), event("b", 2), event("c", 3)];
+        let ids: Vec<String> = newest_first(events.clone(), 10)
+            .into_iter()
+            .map(|e| e.id)
+            .collect();
+        assert_eq!(ids, ["a", "b", "c"]);
+        assert_eq!(newest_first(events, 2).len(), 2);
+    }
+
+    #[test]
+    fn csv_field_quotes_only_when_needed() {
+        assert_eq!(csv_field("app.created"), "app.created");
+        assert_eq!(csv_field(""), "");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field(r#"{"name":"web"}"#), r#""{""name"":""web""}""#);
+    }
+}
@@ -0,0 +1,107 @@
+use clap::Parser;
+use colored::Colorize;
+use inquire::{Confirm, Select, Text};
+use std::io::IsTerminal;
+
+use crate::commands::{link, login};
+use crate::config::Config;
+use crate::errors::{QuomeError, Result};
+use crate::settings::Settings;
+
+const PRODUCTION: &str = "Production (https://quome.studio)";
+const CUSTOM: &str = "Custom URL...";
+
+#[derive(Parser)]
+pub struct Args {
+    /// API base URL to use (skips the environment prompt)
+    #[arg(long)]
+    api_url: Option<String>,
+
+    /// Don't offer to link the current directory
+    #[arg(long)]
+    no_link: bool,
+}
+
+fn prompt_err(e: inquire::InquireError) -> QuomeError {
+    QuomeError::Io(std::io::Error::other(e.to_string()))
+}
+
+fn done(step: &str, detail: &str) {
+    println!("{} {} {}", "✓".green(), step, detail.dimmed());
+}
+
+pub async fn execute(args: Args) -> Result<()> {
+    if !std::io::stdin().is_terminal() {
+        return Err(QuomeError::ApiError(
+            "`quome init` is interactive. In scripts use `quome login --token` and `quome link --org`."
+                .into(),
+        ));
+    }
+
+    println!("{}", "Welcome to Quome! Let's get you set up.".bold());
+    println!();
+
+    // 1. API environment — only asked once; an existing choice is kept
+    if let Some(url) = args.api_url {
+        save_api_url(url)?;
+    } else if Settings::is_configured() {
+        done("API", &Settings::load()?.get_api_url());
+    } else {
+        let choice = Select::new("API environment:", vec![PRODUCTION, CUSTOM])
+            .prompt()
+            .map_err(prompt_err)?;
+        let url = match choice {
+            CUSTOM => Text::new("API URL:")
+                .with_help_message("e.g. https://quome.example.com")
+                .prompt()
+                .map_err(prompt_err)?,
+            _ => Settings::default().api_url,
+        };
+        save_api_url(url)?;
+    }
+
+    // 2. Login
+    let config = Config::load()?;
+    match (&config.user, config.get_token_string()) {
+        (Some(user), _) => done("Logged in as", &user.email),
+        (None, Some(_)) => done("Logged in via", "QUOME_TOKEN"),
+        (None, None) => login::execute(login::Args::default()).await?,
+    }
+
+    // 3. Link the current directory (optional)
+    let config = Config::load()?;
+    if let Some(linked) = config.get_linked()? {
+        let target = match linked.app_name {
+            Some(ref app) => format!("{} / {}", linked.org_name, app),
+            None => linked.org_name.clone(),
+        };
+        done("Linked to", &target);
+    } else if !args.no_link {
+        let link_now = Confirm::new("Link this directory to an organization and app?")
+            .with_default(true)
+            .prompt()
+            .map_err(prompt_err)?;
+        if link_now {
+            link::execute(link::Args::default()).await?;
+        }
+    }
+
+    println!();
+    println!(
+        "You're all set. Try {} or {}.",
+        "quome apps list".cyan(),
+        "quome apps create".cyan()
+    );
+
+    Ok(())
+}
+
+fn save_api_url(url: String) -> Result<()> {
+    let settings = Settings {
+        api_url: url.trim_end_matches('/').to_string(),
+        ..Settings::load()?
+    };
+    settings.save_global()?;
+    done("API", &settings.api_url);
+    Ok(())
+}
@@ -2,11 +2,14 @@ use clap::{Parser, Subcommand};
 use colored::Colorize;
 use uuid::Uuid;
 
-use crate::api::models::CreateApiKeyRequest;
-use crate::client::QuomeClient;
+use crate::api::models::{ApiKey, CreateApiKeyRequest};
+use crate::bulk;
+use crate::cache;
+use crate::client::{self, QuomeClient};
 use crate::config::Config;
+use crate::context::OrgArgs;
 use crate::errors::Result;
-use crate::ui::{self, KeyRow};
+use crate::ui::{self, KeyRow, TimeStyle};
 
 #[derive(Subcommand)]
 pub enum KeysCommands {
@@ -20,9 +23,12 @@ pub enum KeysCommands {
 
 #[derive(Parser)]
 pub struct ListArgs {
-    /// Organization ID (uses linked org if not provided)
+    #[command(flatten)]
+    org: OrgArgs,
+
+    /// Serve the last cached result instead of calling the API
     #[arg(long)]
-    org: Option<Uuid>,
+    offline: bool,
 
     /// Output as JSON
     #[arg(long)]
@@ -46,27 +52,42 @@ pub struct CreateArgs {
     #[arg(long, default_value = "0")]
     expires_days: u32,
 
-    /// Organization ID (uses linked org if not provided)
-    #[arg(long)]
-    org: Option<Uuid>,
+    #[command(flatten)]
+    org: OrgArgs,
 
     /// Output as JSON
     #[arg(long)]
     json: bool,
+
+    /// Print only the new key, without a trailing newline
+    #[arg(long, conflicts_with = "json")]
+    raw: bool,
 }
 
 #[derive(Parser)]
 pub struct DeleteArgs {
     /// API key ID
-    id: Uuid,
+    #[arg(required_unless_present = "expired", conflicts_with = "expired")]
+    id: Option<Uuid>,
 
-    /// Organization ID (uses linked org if not provided)
+    /// Delete every key whose expiration has passed
     #[arg(long)]
-    org: Option<Uuid>,
+    expired: bool,
+
+    /// Stop starting new deletions after the first failure (with --expired)
+    #[arg(long, requires = "expired")]
+    fail_fast: bool,
+
+    #[command(flatten)]
+    org: OrgArgs,
 
     /// Skip confirmation prompt
     #[arg(short, long)]
     force: bool,
+
+    /// Output the batch result as JSON (with --expired)
+    #[arg(long, requires = "expired")]
+    json: bool,
 }
 
 pub async fn execute(command: KeysCommands) -> Result<()> {
@@ -77,40 +98,45 @@ pub async fn execute(command: KeysCommands) -> Result<()> {
     }
 }
 
+pub(crate) fn key_rows(keys: &[ApiKey]) -> Vec<KeyRow> {
+    keys.iter()
+        .map(|key| KeyRow {
+            id: key.id.to_string(),
+            name: key.name.clone(),
+            prefix: key.key_prefix.clone(),
+            created: ui::fmt_time(&key.created_at, TimeStyle::List),
+        })
+        .collect()
+}
+
 async fn list(args: ListArgs) -> Result<()> {
     let config = Config::load()?;
     let token = config.require_token()?;
-
-    let org_id = match args.org {
-        Some(id) => id,
-        None => config.require_linked_org()?,
-    };
-
     let client = QuomeClient::new(Some(&token), None)?;
 
+    let org_id = args
+        .org
+        .resolve_cached(&config, &client, args.offline)
+        .await?;
+
     let sp = ui::spinner("Fetching API keys...");
-    let keys = client.list_org_keys(org_id).await?;
+    let keys = cache::through(
+        &format!("keys-{}", org_id),
+        args.offline,
+        client.list_org_keys(org_id),
+    )
+    .await?;
     sp.finish_and_clear();
 
-    if args.json {
-        println!("{}", serde_json::to_string_pretty(&keys)?);
+    if ui::structured(args.json) {
+        ui::print_structured(&keys)?;
     } else {
         if keys.is_empty() {
             println!("No API keys found.");
             return Ok(());
         }
 
-        let rows: Vec<KeyRow> = keys
-            .iter()
-            .map(|key| KeyRow {
-                id: key.id.to_string(),
-                name: key.name.clone(),
-                prefix: key.key_prefix.clone(),
-                created: key.created_at.format("%Y-%m-%d %H:%M").to_string(),
-            })
-            .collect();
-
-        ui::print_table(rows);
+        ui::print_table(key_rows(&keys));
     }
 
     Ok(())
@@ -119,11 +145,9 @@ async fn list(args: ListArgs) -> Result<()> {
 async fn create(args: CreateArgs) -> Result<()> {
     let config = Config::load()?;
     let token = config.require_token()?;
+    let client = QuomeClient::new(Some(&token), None)?;
 
-    let org_id = match args.org {
-        Some(id) => id,
-        None => config.require_linked_org()?,
-    };
+    let org_id = args.org.resolve(&config, &client).await?;
 
     let expires_in_days = if args.expires_days > 0 {
         Some(args.expires_days)
@@ -131,8 +155,6 @@ async fn create(args: CreateArgs) -> Result<()> {
         None
     };
 
-    let client = QuomeClient::new(Some(&token), None)?;
-
     let sp = ui::spinner("Creating API key...");
     let key = client
         .create_org_key(
@@ -147,8 +169,10 @@ async fn create(args: CreateArgs) -> Result<()> {
         .await?;
     sp.finish_and_clear();
 
-    if args.json {
-        println!("{}", serde_json::to_string_pretty(&key)?);
+    if ui::structured(args.json) {
+        ui::print_structured(&key)?;
+    } else if args.raw {
+        ui::print_raw(&key.key)?;
     } else {
         ui::print_success(
             "Created API key",
@@ -168,16 +192,18 @@ async fn create(args: CreateArgs) -> Result<()> {
 async fn delete(args: DeleteArgs) -> Result<()> {
     let config = Config::load()?;
     let token = config.require_token()?;
+    let client = QuomeClient::new(Some(&token), None)?;
 
-    let org_id = match args.org {
-        Some(id) => id,
-        None => config.require_linked_org()?,
+    let org_id = args.org.resolve(&config, &client).await?;
+
+    let Some(key_id) = args.id else {
+        return delete_expired(args, org_id, &client).await;
     };
 
     if !args.force {
         let confirm = inquire::Confirm::new(&format!(
             "Are you sure you want to delete API key {}?",
-            args.id
+            key_id
         ))
         .with_default(false)
         .prompt()
@@ -189,13 +215,55 @@ async fn delete(args: DeleteArgs) -> Result<()> {
         }
     }
 
-    let client = QuomeClient::new(Some(&token), None)?;
-
     let sp = ui::spinner("Deleting API key...");
-    client.delete_org_key(org_id, args.id).await?;
+    client.delete_org_key(org_id, key_id).await?;
     sp.finish_and_clear();
 
-    ui::print_success("Deleted API key", &[("ID", &args.id.to_string())]);
+    ui::print_success("Deleted API key", &[("ID", &key_id.to_string())]);
 
     Ok(())
 }
+
+async fn delete_expired(args: DeleteArgs, org_id: Uuid, client: &QuomeClient) -> Result<()> {
+    let sp = ui::spinner("Fetching API keys...");
+    let keys = client.list_org_keys(org_id).await?;
+    sp.finish_and_clear();
+
+    // By the API's clock, so a badly set local clock can't misjudge expiry
+    let now = client::server_now();
+    let expired: Vec<ApiKey> = keys
+        .into_iter()
+        .filter(|k| k.expires_at.is_some_and(|exp| exp <= now))
+        .collect();
+
+    if expired.is_empty() {
+        println!("No expired API keys.");
+        return Ok(());
+    }
+
+    let names: Vec<String> = expired
+        .iter()
+        .map(|k| {
+            let expired_on = k
+                .expires_at
+                .map(|exp| ui::fmt_time(&exp, TimeStyle::Date))
+                .unwrap_or_default();
+            format!("{} ({}..., expired {})", k.name, k.key_prefix, expired_on)
+        })
+        .collect();
+
+    if !bulk::confirm("Delete", "expired API keys", &names, args.force)? {
+        println!("Cancelled.");
+        return Ok(());
+    }
+
+    let items = names
+        .into_iter()
+        .zip(expired.iter().map(|k| k.id))
+        .collect();
+    bulk::run_batch("Deleted", items, args.fail_fast, |id| {
+        client.delete_org_key(org_id, id)
+    })
+    .await
+    .report(ui::structured(args.json))
+}
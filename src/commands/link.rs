@@ -6,7 +6,7 @@ use crate::config::{Config, LinkedContext};
 use crate::errors::Result;
 use crate::ui;
 
-#[derive(Parser)]
+#[derive(Parser, Default)]
 pub struct Args {
     /// Organization ID (skips interactive selection)
     #[arg(long)]
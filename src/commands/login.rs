@@ -1,33 +1,56 @@
 use clap::Parser;
+use std::io::IsTerminal;
 
+use crate::cache;
 use crate::client::QuomeClient;
+use crate::commands::whoami;
 use crate::config::Config;
-use crate::errors::Result;
+use crate::errors::{QuomeError, Result};
 use crate::ui;
 
-#[derive(Parser)]
+#[derive(Parser, Default)]
 pub struct Args {
     /// API key (will prompt if not provided)
     #[arg(short, long)]
     token: Option<String>,
+
+    /// Replace an existing session without asking
+    #[arg(short, long)]
+    force: bool,
 }
 
 pub async fn execute(args: Args) -> Result<()> {
-    // Check if already logged in
+    // Check if already logged in with a session the API still accepts
     let config = Config::load()?;
-    if let Some(user) = &config.user {
-        ui::print_detail(
-            "Already logged in",
-            &[("Email", &user.email), ("User ID", &user.id.to_string())],
-        );
-
-        let confirm = inquire::Confirm::new("Do you want to login with a different token?")
-            .with_default(false)
-            .prompt()
-            .map_err(|e| crate::errors::QuomeError::Io(std::io::Error::other(e.to_string())))?;
+    if let (Some(user), false) = (&config.user, args.force) {
+        let client = QuomeClient::new(Some(&user.token), None)?;
+
+        let sp = ui::spinner("Checking existing session...");
+        let current = client.get_current_user().await;
+        sp.finish_and_clear();
+
+        // A rejected token is a dead session — just replace it. Any other
+        // failure (e.g. offline) can't prove that, so fall back to the stored email.
+        let email = match current {
+            Ok(u) => Some(u.email),
+            Err(QuomeError::Unauthorized) => None,
+            Err(_) => Some(user.email.clone()),
+        };
+
+        if let Some(email) = email {
+            if !std::io::stdin().is_terminal() {
+                return Err(QuomeError::AlreadyLoggedIn(email));
+            }
+
+            let confirm =
+                inquire::Confirm::new(&format!("Already logged in as {}, re-login?", email))
+                    .with_default(false)
+                    .prompt()
+                    .map_err(|e| QuomeError::Io(std::io::Error::other(e.to_string())))?;
 
-        if !confirm {
-            return Ok(());
+            if !confirm {
+                return Ok(());
+            }
         }
     }
 
@@ -37,7 +60,7 @@ pub async fn execute(args: Args) -> Result<()> {
             .without_confirmation()
             .with_help_message("Generate an API key from the Quome dashboard")
             .prompt()
-            .map_err(|e| crate::errors::QuomeError::Io(std::io::Error::other(e.to_string())))?,
+            .map_err(|e| QuomeError::Io(std::io::Error::other(e.to_string())))?,
     };
 
     let sp = ui::spinner("Validating token...");
@@ -50,6 +73,7 @@ pub async fn execute(args: Args) -> Result<()> {
     let mut config = Config::load()?;
     config.set_user(token, user.id, user.email.clone());
     config.save()?;
+    cache::write(whoami::CACHE_ENTRY, &user);
 
     sp.finish_and_clear();
 
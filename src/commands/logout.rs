@@ -1,6 +1,7 @@
 use clap::Parser;
 use colored::Colorize;
 
+use crate::cache;
 use crate::config::Config;
 use crate::errors::Result;
 
@@ -10,6 +11,11 @@ pub struct Args {}
 pub async fn execute(_args: Args) -> Result<()> {
     let mut config = Config::load()?;
 
+    // Cached lists and the whoami entry belong to whoever was logged in, and
+    // a migration backup may still hold their token
+    cache::clear()?;
+    Config::remove_backups()?;
+
     if config.user.is_none() {
         println!("Not logged in.");
         return Ok(());
@@ -1,63 +1,191 @@
-use clap::Parser;
+use chrono::{DateTime, Utc};
+use clap::{Parser, ValueEnum};
 use colored::Colorize;
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
+use std::fs::{File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use uuid::Uuid;
 
+use crate::api::models::{AppLogs, LogEntry, RevisionLogs};
 use crate::client::QuomeClient;
 use crate::config::Config;
-use crate::errors::Result;
-use crate::ui;
+use crate::context::{AppArgs, OrgArgs};
+use crate::errors::{QuomeError, Result};
+use crate::ui::{self, LevelCountRow, MessageCountRow, TimeStyle};
+use crate::util;
 
 #[derive(Parser)]
 pub struct Args {
-    /// Application ID (uses linked app if not provided)
-    #[arg(long)]
-    app: Option<Uuid>,
+    #[command(flatten)]
+    app: AppArgs,
 
-    /// Organization ID (uses linked org if not provided)
-    #[arg(long)]
-    org: Option<Uuid>,
+    #[command(flatten)]
+    org: OrgArgs,
 
-    /// Number of log entries to fetch
+    /// Number of log entries to fetch (the most recent ones)
     #[arg(short = 'n', long, default_value = "200")]
     limit: u32,
 
-    /// Output as JSON
-    #[arg(long)]
+    /// Show only the newest N entries, like `tail -n`
+    #[arg(long, value_name = "N", conflicts_with_all = ["limit", "head"])]
+    tail: Option<u32>,
+
+    /// Show only the oldest N of the fetched entries, like `head -n`
+    #[arg(long, value_name = "N")]
+    head: Option<u32>,
+
+    /// Only entries at or after this time: RFC 3339, YYYY-MM-DD, or a duration ago like 15m, 2h, 1d
+    #[arg(long, value_name = "TIME")]
+    since: Option<String>,
+
+    /// Only entries before this time (same formats as --since)
+    #[arg(long, value_name = "TIME", conflicts_with = "follow")]
+    until: Option<String>,
+
+    /// Only entries at or above this severity; -n then counts matching entries
+    #[arg(long, value_enum, ignore_case = true)]
+    level: Option<LogLevel>,
+
+    /// Summarize the fetched entries (counts per level, top errors) instead of printing them
+    #[arg(long, conflicts_with = "output_file")]
+    stats: bool,
+
+    /// Keep polling and print new entries as they arrive, until Ctrl-C
+    #[arg(short, long, conflicts_with_all = ["stats", "head"])]
+    follow: bool,
+
+    /// Seconds between polls with --follow
+    #[arg(
+        long,
+        value_name = "SECS",
+        default_value = "2",
+        requires = "follow",
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    poll_interval: u64,
+
+    /// Output as JSON (one object per line with --follow)
+    #[arg(long, conflicts_with = "output_file")]
     json: bool,
+
+    /// Append entries to this file (plain text) instead of printing them
+    #[arg(long, value_name = "PATH")]
+    output_file: Option<PathBuf>,
+
+    /// Roll the output file over to <PATH>.1 once it reaches this many MB
+    #[arg(
+        long,
+        value_name = "MB",
+        requires = "output_file",
+        value_parser = clap::value_parser!(u64).range(1..)
+    )]
+    rotate_size: Option<u64>,
 }
 
-fn severity_color(severity: &str) -> colored::ColoredString {
+/// Minimum severity for `--level`, lowest first so the derived order is
+/// "at or above".
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, ValueEnum)]
+enum LogLevel {
+    Debug,
+    Info,
+    Warn,
+    Error,
+}
+
+impl LogLevel {
+    /// The level of an API severity; anything unrecognised counts as info.
+    fn of(severity: Option<&str>) -> Self {
+        match severity_label(severity.unwrap_or("INFO")).trim_end() {
+            "DEBUG" => Self::Debug,
+            "WARN" => Self::Warn,
+            "ERROR" => Self::Error,
+            _ => Self::Info,
+        }
+    }
+}
+
+/// With `--level`, the API is asked for this many times `-n` entries, since
+/// it can't filter by severity and `-n` counts only the matching ones.
+const LEVEL_OVERFETCH: u32 = 5;
+
+/// Five-column severity label shared by the terminal and `--output-file`.
+fn severity_label(severity: &str) -> String {
     match severity.to_uppercase().as_str() {
-        "DEBUG" => "DEBUG".dimmed(),
-        "INFO" | "DEFAULT" | "NOTICE" => "INFO ".blue(),
-        "WARNING" | "WARN" => "WARN ".yellow(),
-        "ERROR" | "CRITICAL" | "ALERT" | "EMERGENCY" => "ERROR".red(),
-        other => other.normal(),
+        "DEBUG" => "DEBUG".to_string(),
+        "INFO" | "DEFAULT" | "NOTICE" => "INFO ".to_string(),
+        "WARNING" | "WARN" => "WARN ".to_string(),
+        "ERROR" | "CRITICAL" | "ALERT" | "EMERGENCY" => "ERROR".to_string(),
+        other => other.to_string(),
+    }
+}
+
+fn severity_color(severity: &str) -> colored::ColoredString {
+    let label = severity_label(severity);
+    match label.as_str() {
+        "DEBUG" => label.dimmed(),
+        "INFO " => label.blue(),
+        "WARN " => label.yellow(),
+        "ERROR" => label.red(),
+        _ => label.normal(),
     }
 }
 
 pub async fn execute(args: Args) -> Result<()> {
+    // --json already conflicts with --output-file; -o is global, so clap can't
+    // see the clash
+    if args.output_file.is_some() && ui::structured(false) {
+        return Err(QuomeError::ApiError(
+            "--output-file writes plain text; drop -o to use it".into(),
+        ));
+    }
+
+    let since = args.since.as_deref().map(util::parse_date).transpose()?;
+    let until = args.until.as_deref().map(util::parse_date).transpose()?;
+
     let config = Config::load()?;
     let token = config.require_token()?;
+    let client = QuomeClient::new(Some(&token), None)?;
 
-    let org_id = match args.org {
-        Some(id) => id,
-        None => config.require_linked_org()?,
-    };
+    let org_id = args.org.resolve(&config, &client).await?;
 
-    let app_id = match args.app {
-        Some(id) => id,
-        None => config.require_linked_app()?,
-    };
+    let app_id = args.app.resolve(&config, &client, org_id).await?;
 
-    let client = QuomeClient::new(Some(&token), None)?;
+    let limit = args.tail.unwrap_or(args.limit);
+
+    let fetch = if args.level.is_some() {
+        limit.saturating_mul(LEVEL_OVERFETCH)
+    } else {
+        limit
+    };
 
     let sp = ui::spinner("Fetching logs...");
-    let logs = client.get_logs(org_id, app_id, Some(args.limit)).await?;
+    let logs = client
+        .get_logs(org_id, app_id, Some(fetch), since, until)
+        .await?;
     sp.finish_and_clear();
 
-    if args.json {
-        println!("{}", serde_json::to_string_pretty(&logs)?);
+    // The API should already honour the range; enforce it in case it doesn't
+    let logs = within(logs, since, until);
+
+    let logs = match args.level {
+        Some(level) => at_least(logs, level, limit as usize),
+        None => logs,
+    };
+    let logs = chronological(logs, args.head.map(|n| n as usize));
+
+    if args.follow {
+        return follow(&client, org_id, app_id, fetch, logs, &args).await;
+    }
+
+    if args.stats {
+        return print_stats(&log_stats(&logs), ui::structured(args.json));
+    }
+
+    if ui::structured(args.json) {
+        ui::print_structured(&logs)?;
         return Ok(());
     }
 
@@ -66,18 +194,37 @@ pub async fn execute(args: Args) -> Result<()> {
         return Ok(());
     }
 
-    // Logs are grouped by Cloud Run revision; print each group as a stream
+    if let Some(path) = &args.output_file {
+        let mut sink = FileSink::open(
+            path,
+            args.rotate_size.map(|mb| mb.saturating_mul(1024 * 1024)),
+        )?;
+        let mut count = 0;
+        for revision in &logs.revisions {
+            sink.write_line(&format!("── {} ──", revision.revision_name));
+            for entry in &revision.logs {
+                let severity = entry.severity.as_deref().unwrap_or("INFO");
+                let written = sink.write_line(&format!(
+                    "{} {} {}",
+                    ui::fmt_time(&entry.timestamp, TimeStyle::Detail),
+                    severity_label(severity),
+                    entry.message
+                ));
+                count += written as usize;
+            }
+        }
+        println!("Wrote {} log entries to {}", count, path.display());
+        return Ok(());
+    }
+
+    // Oldest first; a new header whenever the serving revision changes
     for revision in &logs.revisions {
         println!("{}", format!("── {} ──", revision.revision_name).dimmed());
         for entry in &revision.logs {
             let severity = entry.severity.as_deref().unwrap_or("INFO");
             println!(
                 "{} {} {}",
-                entry
-                    .timestamp
-                    .format("%Y-%m-%d %H:%M:%S")
-                    .to_string()
-                    .dimmed(),
+                ui::fmt_time(&entry.timestamp, TimeStyle::Detail).dimmed(),
                 severity_color(severity),
                 entry.message
             );
@@ -86,3 +233,643 @@ pub async fn execute(args: Args) -> Result<()> {
 
     Ok(())
 }
+
+/// Where `--follow` sends entries.
+enum FollowOutput {
+    Terminal,
+    /// One JSON object per line, flushed as it's written
+    Ndjson,
+    File(FileSink),
+}
+
+/// A followed entry as printed by `--follow --json`.
+#[derive(Serialize)]
+struct FollowedEntry<'a> {
+    revision: &'a str,
+    #[serde(flatten)]
+    entry: &'a LogEntry,
+}
+
+/// `--follow`: print `initial`, then poll every `--poll-interval` seconds and
+/// print only entries not seen yet, until Ctrl-C. Each poll fetches the
+/// newest `limit` entries, so a burst bigger than that between two polls
+/// loses the overflow.
+async fn follow(
+    client: &QuomeClient,
+    org_id: Uuid,
+    app_id: Uuid,
+    limit: u32,
+    initial: AppLogs,
+    args: &Args,
+) -> Result<()> {
+    let mut out = match &args.output_file {
+        Some(path) => {
+            eprintln!("Following logs into {}... Ctrl-C to stop", path.display());
+            let rotate_bytes = args.rotate_size.map(|mb| mb.saturating_mul(1024 * 1024));
+            FollowOutput::File(FileSink::open(path, rotate_bytes)?)
+        }
+        None if ui::structured(args.json) => FollowOutput::Ndjson,
+        None => FollowOutput::Terminal,
+    };
+
+    let mut cursor = FollowCursor::default();
+    let mut revision: Option<String> = None;
+    let mut written = 0;
+    for (rev, entry) in cursor.fresh(initial) {
+        written += emit(&mut out, &mut revision, &rev, &entry)? as usize;
+    }
+
+    let interval = Duration::from_secs(args.poll_interval);
+    let ctrl_c = tokio::signal::ctrl_c();
+    tokio::pin!(ctrl_c);
+    loop {
+        let poll = async {
+            tokio::time::sleep(interval).await;
+            client
+                .get_logs(org_id, app_id, Some(limit), None, None)
+                .await
+        };
+        let logs = tokio::select! {
+            _ = &mut ctrl_c => break,
+            logs = poll => match args.level {
+                Some(level) => at_least(logs?, level, usize::MAX),
+                None => chronological(logs?, None),
+            },
+        };
+        for (rev, entry) in cursor.fresh(logs) {
+            written += emit(&mut out, &mut revision, &rev, &entry)? as usize;
+        }
+    }
+
+    if let Some(path) = &args.output_file {
+        eprintln!("Wrote {} log entries to {}", written, path.display());
+    }
+    Ok(())
+}
+
+/// Print one followed entry, with a revision header whenever the serving
+/// revision changes. Returns whether it was written.
+fn emit(
+    out: &mut FollowOutput,
+    current: &mut Option<String>,
+    revision: &str,
+    entry: &LogEntry,
+) -> Result<bool> {
+    let header = current.as_deref() != Some(revision);
+    *current = Some(revision.to_string());
+    let severity = entry.severity.as_deref().unwrap_or("INFO");
+    match out {
+        FollowOutput::Terminal => {
+            if header {
+                println!("{}", format!("── {} ──", revision).dimmed());
+            }
+            println!(
+                "{} {} {}",
+                ui::fmt_time(&entry.timestamp, TimeStyle::Detail).dimmed(),
+                severity_color(severity),
+                entry.message
+            );
+        }
+        FollowOutput::Ndjson => {
+            let mut stdout = std::io::stdout().lock();
+            serde_json::to_writer(&mut stdout, &FollowedEntry { revision, entry })?;
+            writeln!(stdout)?;
+            stdout.flush()?;
+        }
+        FollowOutput::File(sink) => {
+            if header {
+                sink.write_line(&format!("── {} ──", revision));
+            }
+            return Ok(sink.write_line(&format!(
+                "{} {} {}",
+                ui::fmt_time(&entry.timestamp, TimeStyle::Detail),
+                severity_label(severity),
+                entry.message
+            )));
+        }
+    }
+    Ok(true)
+}
+
+/// What `--follow` has already printed. The logs API has no cursor, so polls
+/// overlap; entries are told apart by timestamp and message, and only the
+/// messages at the newest timestamp need remembering.
+#[derive(Default)]
+struct FollowCursor {
+    newest: Option<DateTime<Utc>>,
+    at_newest: HashSet<String>,
+}
+
+impl FollowCursor {
+    /// The entries of a [`chronological`] batch not printed before, oldest
+    /// first, paired with their revision name.
+    fn fresh(&mut self, logs: AppLogs) -> Vec<(String, LogEntry)> {
+        let mut fresh = Vec::new();
+        for revision in logs.revisions {
+            for entry in revision.logs {
+                let is_new = match self.newest {
+                    None => true,
+                    Some(newest) => {
+                        entry.timestamp > newest
+                            || (entry.timestamp == newest
+                                && !self.at_newest.contains(&entry.message))
+                    }
+                };
+                if !is_new {
+                    continue;
+                }
+                if self.newest != Some(entry.timestamp) {
+                    self.newest = Some(entry.timestamp);
+                    self.at_newest.clear();
+                }
+                self.at_newest.insert(entry.message.clone());
+                fresh.push((revision.revision_name.clone(), entry));
+            }
+        }
+        fresh
+    }
+}
+
+/// How many distinct error messages `--stats` lists.
+const TOP_ERRORS: usize = 5;
+
+/// `--stats` summary of the fetched window.
+#[derive(Debug, Serialize)]
+struct LogStats {
+    total: usize,
+    /// Entry count per severity label, most frequent first
+    levels: Vec<LevelCount>,
+    errors: usize,
+    /// Fraction of entries at ERROR or above
+    error_rate: f64,
+    /// Most repeated error messages, most frequent first
+    top_errors: Vec<MessageCount>,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct LevelCount {
+    level: String,
+    count: usize,
+}
+
+#[derive(Debug, PartialEq, Serialize)]
+struct MessageCount {
+    message: String,
+    count: usize,
+}
+
+fn log_stats(logs: &AppLogs) -> LogStats {
+    let mut levels: HashMap<String, usize> = HashMap::new();
+    let mut errors: HashMap<&str, usize> = HashMap::new();
+    let mut total = 0;
+    for entry in logs.revisions.iter().flat_map(|r| &r.logs) {
+        total += 1;
+        let label = severity_label(entry.severity.as_deref().unwrap_or("INFO"));
+        let label = label.trim_end().to_string();
+        if label == "ERROR" {
+            // Group on the first line so stack traces with the same cause match
+            let first_line = entry.message.lines().next().unwrap_or_default().trim();
+            *errors.entry(first_line).or_default() += 1;
+        }
+        *levels.entry(label).or_default() += 1;
+    }
+
+    // Ties broken by name so the output is stable
+    let by_count = |a: &(String, usize), b: &(String, usize)| b.1.cmp(&a.1).then(a.0.cmp(&b.0));
+    let mut levels: Vec<(String, usize)> = levels.into_iter().collect();
+    levels.sort_by(by_count);
+    let error_count = errors.values().sum();
+    let mut top_errors: Vec<(String, usize)> = errors
+        .into_iter()
+        .map(|(m, n)| (m.to_string(), n))
+        .collect();
+    top_errors.sort_by(by_count);
+    top_errors.truncate(TOP_ERRORS);
+
+    let levels = levels
+        .into_iter()
+        .map(|(level, count)| LevelCount { level, count })
+        .collect();
+    let top_errors = top_errors
+        .into_iter()
+        .map(|(message, count)| MessageCount { message, count })
+        .collect();
+
+    LogStats {
+        total,
+        levels,
+        errors: error_count,
+        error_rate: if total == 0 {
+            0.0
+        } else {
+            error_count as f64 / total as f64
+        },
+        top_errors,
+    }
+}
+
+fn print_stats(stats: &LogStats, json: bool) -> Result<()> {
+    if json {
+        ui::print_structured(stats)?;
+        return Ok(());
+    }
+    if stats.total == 0 {
+        println!("No logs found.");
+        return Ok(());
+    }
+
+    let share = |n: usize| format!("{:.1}%", n as f64 * 100.0 / stats.total as f64);
+    ui::print_table(
+        stats
+            .levels
+            .iter()
+            .map(|l| LevelCountRow {
+                level: severity_color(&l.level).to_string(),
+                count: l.count,
+                share: share(l.count),
+            })
+            .collect(),
+    );
+    println!(
+        "{} entries, error rate {}",
+        stats.total,
+        share(stats.errors)
+    );
+
+    if !stats.top_errors.is_empty() {
+        println!();
+        println!("{}", "Top errors".bold());
+        ui::print_table(
+            stats
+                .top_errors
+                .iter()
+                .map(|e| MessageCountRow {
+                    count: e.count,
+                    message: truncate(&e.message, 80),
+                })
+                .collect(),
+        );
+    }
+    Ok(())
+}
+
+fn truncate(s: &str, max: usize) -> String {
+    match s.char_indices().nth(max) {
+        Some((i, _)) => format!("{}…", &s[..i]),
+        None => s.to_string(),
+    }
+}
+
+/// `--output-file`: appends lines and flushes each one so `tail -f` stays
+/// current. Past `rotate_bytes` the file is renamed to `<path>.1` (replacing
+/// the previous one) and a fresh file started. A failed write (disk full,
+/// file removed) is reported once and later lines keep trying, so a long
+/// capture recovers once space is freed.
+struct FileSink {
+    path: PathBuf,
+    file: File,
+    written: u64,
+    rotate_bytes: Option<u64>,
+    failing: bool,
+}
+
+impl FileSink {
+    fn open(path: &Path, rotate_bytes: Option<u64>) -> Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        let written = file.metadata()?.len();
+        Ok(Self {
+            path: path.to_path_buf(),
+            file,
+            written,
+            rotate_bytes,
+            failing: false,
+        })
+    }
+
+    /// Whether the line made it to disk.
+    fn write_line(&mut self, line: &str) -> bool {
+        match self.try_write(line) {
+            Ok(()) => {
+                if self.failing {
+                    self.failing = false;
+                    eprintln!("{} Writing to {} again", "✓".green(), self.path.display());
+                }
+                true
+            }
+            Err(e) => {
+                if !self.failing {
+                    self.failing = true;
+                    eprintln!(
+                        "{} Couldn't write to {}: {} (entries are being dropped)",
+                        "⚠".yellow(),
+                        self.path.display(),
+                        e
+                    );
+                }
+                false
+            }
+        }
+    }
+
+    fn try_write(&mut self, line: &str) -> std::io::Result<()> {
+        let len = line.len() as u64 + 1;
+        if self
+            .rotate_bytes
+            .is_some_and(|max| self.written > 0 && self.written + len > max)
+        {
+            let mut rotated = self.path.clone().into_os_string();
+            rotated.push(".1");
+            std::fs::rename(&self.path, rotated)?;
+            self.file = File::create(&self.path)?;
+            self.written = 0;
+        }
+        writeln!(self.file, "{}", line)?;
+        self.file.flush()?;
+        self.written += len;
+        Ok(())
+    }
+}
+
+/// Only the entries in `[since, until)`.
+fn within(
+    mut logs: AppLogs,
+    since: Option<DateTime<Utc>>,
+    until: Option<DateTime<Utc>>,
+) -> AppLogs {
+    for revision in &mut logs.revisions {
+        revision.logs.retain(|e| {
+            since.iter().all(|s| e.timestamp >= *s) && until.iter().all(|u| e.timestamp < *u)
+        });
+    }
+    logs.revisions.retain(|r| !r.logs.is_empty());
+    logs
+}
+
+/// The newest `keep` entries at or above `level`, in [`chronological`] order.
+fn at_least(logs: AppLogs, level: LogLevel, keep: usize) -> AppLogs {
+    let mut logs = chronological(logs, None);
+    for revision in &mut logs.revisions {
+        revision
+            .logs
+            .retain(|e| LogLevel::of(e.severity.as_deref()) >= level);
+    }
+    logs.revisions.retain(|r| !r.logs.is_empty());
+
+    let total: usize = logs.revisions.iter().map(|r| r.logs.len()).sum();
+    let mut drop = total.saturating_sub(keep);
+    for revision in &mut logs.revisions {
+        let n = drop.min(revision.logs.len());
+        revision.logs.drain(..n);
+        drop -= n;
+    }
+    logs.revisions.retain(|r| !r.logs.is_empty());
+    // Emptied runs can leave two runs of one revision side by side
+    chronological(logs, None)
+}
+
+/// Flatten the per-revision groups into one oldest-first stream, keep the
+/// first `head` entries if given, and regroup consecutive runs by revision.
+fn chronological(logs: AppLogs, head: Option<usize>) -> AppLogs {
+    let mut entries: Vec<(String, LogEntry)> = logs
+        .revisions
+        .into_iter()
+        .flat_map(|r| {
+            let name = r.revision_name;
+            r.logs.into_iter().map(move |e| (name.clone(), e))
+        })
+        .collect();
+    entries.sort_by_key(|(_, e)| e.timestamp);
+    if let Some(n) = head {
+        entries.truncate(n);
+    }
+
+    let mut revisions: Vec<RevisionLogs> = Vec::new();
+    for (name, entry) in entries {
+        match revisions.last_mut() {
+            Some(last) if last.revision_name == name => last.logs.push(entry),
+            _ => revisions.push(RevisionLogs {
+                revision_name: name,
+                logs: vec![entry],
+            }),
+        }
+    }
+    AppLogs { revisions }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        at_least, chronological, log_stats, within, FileSink, FollowCursor, LevelCount, LogLevel,
+        MessageCount,
+    };
+    use crate::api::models::{AppLogs, LogEntry, RevisionLogs};
+    use chrono::{TimeZone, Utc};
+
+    fn rev(name: &str, secs: &[i64]) -> RevisionLogs {
+        RevisionLogs {
+            revision_name: name.to_string(),
+            logs: secs
+                .iter()
+                .map(|s| LogEntry {
+                    timestamp: Utc.timestamp_opt(*s, 0).unwrap(),
+                    severity: None,
+                    message: s.to_string(),
+                })
+                .collect(),
+        }
+    }
+
+    fn shape(logs: &AppLogs) -> Vec<(String, Vec<String>)> {
+        logs.revisions
+            .iter()
+            .map(|r| {
+                let msgs = r.logs.iter().map(|e| e.message.clone()).collect();
+                (r.revision_name.clone(), msgs)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn orders_oldest_first_and_regroups() {
+        // API order: newest revision first, entries newest first
+        let logs = AppLogs {
+            revisions: vec![rev("r2", &[40, 30]), rev("r1", &[35, 10])],
+        };
+        let s = |v: &[&str]| v.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            shape(&chronological(logs.clone(), None)),
+            vec![
+                ("r1".to_string(), s(&["10"])),
+                ("r2".to_string(), s(&["30"])),
+                ("r1".to_string(), s(&["35"])),
+                ("r2".to_string(), s(&["40"])),
+            ]
+        );
+        assert_eq!(
+            shape(&chronological(logs, Some(2))),
+            vec![
+                ("r1".to_string(), s(&["10"])),
+                ("r2".to_string(), s(&["30"]))
+            ]
+        );
+    }
+
+    #[test]
+    fn within_keeps_the_half_open_range() {
+        let logs = AppLogs {
+            revisions: vec![rev("r2", &[40, 30]), rev("r1", &[20, 10])],
+        };
+        let at = |secs| Some(Utc.timestamp_opt(secs, 0).unwrap());
+        let s = |v: &[&str]| v.iter().map(|x| x.to_string()).collect::<Vec<_>>();
+        assert_eq!(
+            shape(&within(logs.clone(), at(20), at(40))),
+            vec![
+                ("r2".to_string(), s(&["30"])),
+                ("r1".to_string(), s(&["20"]))
+            ]
+        );
+        assert_eq!(shape(&within(logs.clone(), at(35), None)).len(), 1);
+        assert_eq!(shape(&within(logs, None, None)).len(), 2);
+    }
+
+    #[test]
+    fn at_least_filters_by_level_then_keeps_the_newest() {
+        let entry = |secs: i64, severity: &str| LogEntry {
+            timestamp: Utc.timestamp_opt(secs, 0).unwrap(),
+            severity: Some(severity.to_string()),
+            message: format!("{} {}", severity, secs),
+        };
+        let logs = AppLogs {
+            revisions: vec![RevisionLogs {
+                revision_name: "r1".to_string(),
+                logs: vec![
+                    entry(50, "ERROR"),
+                    entry(40, "INFO"),
+                    entry(30, "WARNING"),
+                    entry(20, "CRITICAL"),
+                    entry(10, "warn"),
+                ],
+            }],
+        };
+        let messages = |logs: AppLogs| -> Vec<String> {
+            logs.revisions
+                .into_iter()
+                .flat_map(|r| r.logs)
+                .map(|e| e.message)
+                .collect()
+        };
+
+        assert_eq!(
+            messages(at_least(logs.clone(), LogLevel::Warn, 3)),
+            ["CRITICAL 20", "WARNING 30", "ERROR 50"]
+        );
+        assert_eq!(
+            messages(at_least(logs.clone(), LogLevel::Error, 10)),
+            ["CRITICAL 20", "ERROR 50"]
+        );
+        assert_eq!(messages(at_least(logs, LogLevel::Debug, 10)).len(), 5);
+        assert_eq!(LogLevel::of(None), LogLevel::Info);
+        assert_eq!(LogLevel::of(Some("EMERGENCY")), LogLevel::Error);
+    }
+
+    #[test]
+    fn follow_cursor_skips_entries_already_printed() {
+        let mut cursor = FollowCursor::default();
+        let messages = |fresh: Vec<(String, LogEntry)>| -> Vec<String> {
+            fresh.into_iter().map(|(_, e)| e.message).collect()
+        };
+
+        let first = chronological(
+            AppLogs {
+                revisions: vec![rev("r1", &[20, 10])],
+            },
+            None,
+        );
+        assert_eq!(messages(cursor.fresh(first)), ["10", "20"]);
+
+        // The next poll overlaps and has a second entry at the same second
+        let mut overlap = rev("r1", &[30, 20, 10]);
+        overlap.logs.push(LogEntry {
+            timestamp: Utc.timestamp_opt(20, 0).unwrap(),
+            severity: None,
+            message: "also 20".to_string(),
+        });
+        let second = chronological(
+            AppLogs {
+                revisions: vec![rev("r2", &[40]), overlap],
+            },
+            None,
+        );
+        assert_eq!(messages(cursor.fresh(second)), ["also 20", "30", "40"]);
+
+        let third = chronological(
+            AppLogs {
+                revisions: vec![rev("r2", &[40])],
+            },
+            None,
+        );
+        assert!(cursor.fresh(third).is_empty());
+    }
+
+    #[test]
+    fn file_sink_rotates_past_the_size_limit() {
+        let dir = std::env::temp_dir().join(format!("quome-logs-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("app.log");
+
+        let mut sink = FileSink::open(&path, Some(10)).unwrap();
+        sink.write_line("first");
+        sink.write_line("second");
+        sink.write_line("third");
+
+        let read = |p: &std::path::Path| std::fs::read_to_string(p).unwrap();
+        assert_eq!(read(&dir.join("app.log.1")), "second\n");
+        assert_eq!(read(&path), "third\n");
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn stats_count_levels_and_group_errors() {
+        let entry = |severity: &str, message: &str| LogEntry {
+            timestamp: Utc.timestamp_opt(0, 0).unwrap(),
+            severity: Some(severity.to_string()),
+            message: message.to_string(),
+        };
+        let logs = AppLogs {
+            revisions: vec![RevisionLogs {
+                revision_name: "r1".into(),
+                logs: vec![
+                    entry("INFO", "started"),
+                    entry("DEFAULT", "request"),
+                    entry("WARNING", "slow"),
+                    entry("ERROR", "db timeout\n  at pool.rs:10"),
+                    entry("CRITICAL", "db timeout\n  at pool.rs:42"),
+                    entry("ERROR", "bad request"),
+                ],
+            }],
+        };
+
+        let stats = log_stats(&logs);
+        assert_eq!(stats.total, 6);
+        let level = |level: &str, count| LevelCount {
+            level: level.into(),
+            count,
+        };
+        assert_eq!(
+            stats.levels,
+            vec![level("ERROR", 3), level("INFO", 2), level("WARN", 1)]
+        );
+        assert_eq!(stats.errors, 3);
+        assert_eq!(stats.error_rate, 0.5);
+        let message = |message: &str, count| MessageCount {
+            message: message.into(),
+            count,
+        };
+        assert_eq!(stats.top_errors[0], message("db timeout", 2));
+        assert_eq!(stats.top_errors[1], message("bad request", 1));
+        assert_eq!(
+            serde_json::to_value(&stats.levels[0]).unwrap(),
+            serde_json::json!({"level": "ERROR", "count": 3})
+        );
+    }
+}
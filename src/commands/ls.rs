@@ -0,0 +1,121 @@
+use clap::{Parser, ValueEnum};
+use std::ffi::OsString;
+
+use crate::alias;
+use crate::errors::{QuomeError, Result};
+
+#[derive(Parser)]
+pub struct Args {
+    /// What to list
+    resource: Resource,
+
+    /// Flags for that list command, e.g. --org-name acme --json
+    /// (only declared for --help; `expand` works from the raw argv)
+    #[allow(dead_code)]
+    #[arg(
+        trailing_var_arg = true,
+        allow_hyphen_values = true,
+        value_name = "FLAGS"
+    )]
+    flags: Vec<OsString>,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum Resource {
+    #[value(alias = "app")]
+    Apps,
+    #[value(aliases = ["db", "databases"])]
+    Dbs,
+    #[value(alias = "secret")]
+    Secrets,
+    #[value(alias = "deployment")]
+    Deployments,
+    #[value(alias = "member")]
+    Members,
+    #[value(alias = "key")]
+    Keys,
+    #[value(alias = "event")]
+    Events,
+}
+
+impl Resource {
+    /// The command that lists this resource.
+    fn command(self) -> &'static [&'static str] {
+        match self {
+            Resource::Apps => &["apps", "list"],
+            Resource::Dbs => &["db", "list"],
+            Resource::Secrets => &["secrets", "list"],
+            Resource::Deployments => &["deployments", "list"],
+            Resource::Members => &["members", "list"],
+            Resource::Keys => &["keys", "list"],
+            Resource::Events => &["events"],
+        }
+    }
+}
+
+/// The argv `quome ls` stands for: `argv` with `ls <resource>` replaced by
+/// the list command, found by position so options before `ls` or between it
+/// and the resource stay put. A `--` right after the resource is dropped, so
+/// `quome ls apps -- --json` still passes `--json` on.
+pub fn expand(argv: &[OsString], args: Args, valued: &[String]) -> Result<Vec<OsString>> {
+    let unexpected =
+        || QuomeError::ApiError("couldn't find `ls <resource>` in the arguments".into());
+    let ls_at = alias::command_index(argv, valued)
+        .filter(|&i| argv[i] == "ls")
+        .ok_or_else(unexpected)?;
+    let resource_at =
+        ls_at + alias::command_index(&argv[ls_at..], valued).ok_or_else(unexpected)?;
+
+    let mut rest = argv[resource_at + 1..].iter().peekable();
+    rest.next_if(|a| *a == "--");
+
+    Ok(argv[..ls_at]
+        .iter()
+        .chain(&argv[ls_at + 1..resource_at])
+        .cloned()
+        .chain(args.resource.command().iter().map(OsString::from))
+        .chain(rest.cloned())
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn argv(words: &[&str]) -> Vec<OsString> {
+        words.iter().map(OsString::from).collect()
+    }
+
+    fn expanded(words: &[&str]) -> Vec<OsString> {
+        let args = Args::parse_from(&words[words.iter().position(|w| *w == "ls").unwrap()..]);
+        expand(&argv(words), args, &["-o".to_string()]).unwrap()
+    }
+
+    #[test]
+    fn expands_to_the_list_command() {
+        assert_eq!(
+            expanded(&["quome", "--local", "ls", "dbs", "--org-name", "acme"]),
+            argv(&["quome", "--local", "db", "list", "--org-name", "acme"])
+        );
+        assert_eq!(
+            expanded(&["quome", "ls", "event", "-n", "5"]),
+            argv(&["quome", "events", "-n", "5"])
+        );
+    }
+
+    #[test]
+    fn drops_a_separator_after_the_resource() {
+        assert_eq!(
+            expanded(&["quome", "--local", "ls", "apps", "--", "--json"]),
+            argv(&["quome", "--local", "apps", "list", "--json"])
+        );
+    }
+
+    #[test]
+    fn keeps_the_output_flag_where_it_was() {
+        assert_eq!(
+            expanded(&["quome", "ls", "apps", "-o", "yaml", "--wide"]),
+            argv(&["quome", "apps", "list", "-o", "yaml", "--wide"])
+        );
+    }
+}
@@ -1,11 +1,16 @@
 use clap::{Parser, Subcommand};
+use std::fs;
+use std::path::{Path, PathBuf};
 use uuid::Uuid;
 
-use crate::api::models::CreateOrgInviteRequest;
+use crate::api::models::{CreateOrgInviteRequest, OrgMember};
+use crate::bulk;
+use crate::cache;
 use crate::client::QuomeClient;
 use crate::config::Config;
-use crate::errors::Result;
-use crate::ui::{self, MemberRow};
+use crate::context::OrgArgs;
+use crate::errors::{QuomeError, Result};
+use crate::ui::{self, MemberRow, TimeStyle};
 
 #[derive(Subcommand)]
 pub enum MembersCommands {
@@ -17,9 +22,12 @@ pub enum MembersCommands {
 
 #[derive(Parser)]
 pub struct ListArgs {
-    /// Organization ID (uses linked org if not provided)
+    #[command(flatten)]
+    org: OrgArgs,
+
+    /// Serve the last cached result instead of calling the API
     #[arg(long)]
-    org: Option<Uuid>,
+    offline: bool,
 
     /// Output as JSON
     #[arg(long)]
@@ -29,15 +37,27 @@ pub struct ListArgs {
 #[derive(Parser)]
 pub struct InviteArgs {
     /// Email address to invite
-    email: String,
+    #[arg(required_unless_present = "from_file", conflicts_with = "from_file")]
+    email: Option<String>,
+
+    /// Invite every email address in this file (one per line; # comments allowed)
+    #[arg(long)]
+    from_file: Option<PathBuf>,
 
     /// Role for the invited member (member or admin)
     #[arg(long, default_value = "member")]
     role: String,
 
-    /// Organization ID (uses linked org if not provided)
-    #[arg(long)]
-    org: Option<Uuid>,
+    #[command(flatten)]
+    org: OrgArgs,
+
+    /// Stop sending invites after the first failure (with --from-file)
+    #[arg(long, requires = "from_file")]
+    fail_fast: bool,
+
+    /// Skip confirmation prompt (with --from-file)
+    #[arg(short, long, requires = "from_file")]
+    force: bool,
 
     /// Output as JSON
     #[arg(long)]
@@ -51,40 +71,46 @@ pub async fn execute(command: MembersCommands) -> Result<()> {
     }
 }
 
+pub(crate) fn member_rows(members: &[OrgMember]) -> Vec<MemberRow> {
+    members
+        .iter()
+        .map(|member| MemberRow {
+            name: member.user_name.clone(),
+            email: member.user_email.clone(),
+            role: member.role.clone(),
+            joined: ui::fmt_time(&member.created_at, TimeStyle::List),
+        })
+        .collect()
+}
+
 async fn list(args: ListArgs) -> Result<()> {
     let config = Config::load()?;
     let token = config.require_token()?;
-
-    let org_id = match args.org {
-        Some(id) => id,
-        None => config.require_linked_org()?,
-    };
-
     let client = QuomeClient::new(Some(&token), None)?;
 
+    let org_id = args
+        .org
+        .resolve_cached(&config, &client, args.offline)
+        .await?;
+
     let sp = ui::spinner("Fetching members...");
-    let members = client.list_org_members(org_id).await?;
+    let members = cache::through(
+        &format!("members-{}", org_id),
+        args.offline,
+        client.list_org_members(org_id),
+    )
+    .await?;
     sp.finish_and_clear();
 
-    if args.json {
-        println!("{}", serde_json::to_string_pretty(&members)?);
+    if ui::structured(args.json) {
+        ui::print_structured(&members)?;
     } else {
         if members.is_empty() {
             println!("No members found.");
             return Ok(());
         }
 
-        let rows: Vec<MemberRow> = members
-            .iter()
-            .map(|member| MemberRow {
-                name: member.user_name.clone(),
-                email: member.user_email.clone(),
-                role: member.role.clone(),
-                joined: member.created_at.format("%Y-%m-%d %H:%M").to_string(),
-            })
-            .collect();
-
-        ui::print_table(rows);
+        ui::print_table(member_rows(&members));
     }
 
     Ok(())
@@ -93,32 +119,32 @@ async fn list(args: ListArgs) -> Result<()> {
 async fn invite(args: InviteArgs) -> Result<()> {
     let config = Config::load()?;
     let token = config.require_token()?;
+    let client = QuomeClient::new(Some(&token), None)?;
 
-    let org_id = match args.org {
-        Some(id) => id,
-        None => config.require_linked_org()?,
-    };
+    let org_id = args.org.resolve(&config, &client).await?;
 
-    let client = QuomeClient::new(Some(&token), None)?;
+    if let Some(ref path) = args.from_file {
+        return invite_from_file(&client, org_id, path, &args).await;
+    }
 
     let sp = ui::spinner("Sending invite...");
     let invite = client
         .create_org_invite(
             org_id,
             &CreateOrgInviteRequest {
-                email: args.email,
+                email: args.email.unwrap_or_default(),
                 role: args.role,
             },
         )
         .await?;
     sp.finish_and_clear();
 
-    if args.json {
-        println!("{}", serde_json::to_string_pretty(&invite)?);
+    if ui::structured(args.json) {
+        ui::print_structured(&invite)?;
     } else {
         let expires = invite
             .expires_at
-            .map(|e| e.format("%Y-%m-%d %H:%M").to_string())
+            .map(|e| ui::fmt_time(&e, TimeStyle::List))
             .unwrap_or_else(|| "-".to_string());
         ui::print_success(
             "Invited member",
@@ -132,3 +158,85 @@ async fn invite(args: InviteArgs) -> Result<()> {
 
     Ok(())
 }
+
+async fn invite_from_file(
+    client: &QuomeClient,
+    org_id: Uuid,
+    path: &Path,
+    args: &InviteArgs,
+) -> Result<()> {
+    let emails = parse_email_list(&fs::read_to_string(path)?).map_err(QuomeError::ApiError)?;
+
+    let sp = ui::spinner("Fetching members...");
+    let members = client.list_org_members(org_id).await?;
+    sp.finish_and_clear();
+
+    let (existing, emails): (Vec<String>, Vec<String>) = emails.into_iter().partition(|email| {
+        members
+            .iter()
+            .any(|m| m.user_email.eq_ignore_ascii_case(email))
+    });
+
+    let mut batch = if emails.is_empty() {
+        bulk::BatchResult::new("Invited")
+    } else {
+        if !bulk::confirm("Invite", "people", &emails, args.force)? {
+            println!("Cancelled.");
+            return Ok(());
+        }
+        let items = emails.iter().map(|e| (e.clone(), e.clone())).collect();
+        bulk::run_batch("Invited", items, args.fail_fast, |email| async move {
+            let request = CreateOrgInviteRequest {
+                email,
+                role: args.role.clone(),
+            };
+            client.create_org_invite(org_id, &request).await
+        })
+        .await
+    };
+    for email in existing {
+        batch.skip(email, "already a member");
+    }
+
+    batch.report(ui::structured(args.json))
+}
+
+/// Emails from an invite list, one per line. Blank lines and `#` comments are
+/// skipped and repeats dropped; anything that isn't an email fails the whole
+/// file up front, naming the line, so a typo never sends half a batch.
+fn parse_email_list(text: &str) -> std::result::Result<Vec<String>, String> {
+    let mut emails: Vec<String> = Vec::new();
+    for (n, line) in text.lines().enumerate() {
+        let line = line.split('#').next().unwrap_or_default().trim();
+        if line.is_empty() {
+            continue;
+        }
+        if !line.contains('@') || line.contains(char::is_whitespace) {
+            return Err(format!(
+                "line {}: '{}' is not an email address (invites are sent by email)",
+                n + 1,
+                line
+            ));
+        }
+        if !emails.iter().any(|e| e.eq_ignore_ascii_case(line)) {
+            emails.push(line.to_string());
+        }
+    }
+    Ok(emails)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::parse_email_list;
+
+    #[test]
+    fn parse_email_list_skips_comments_and_repeats() {
+        let text = "# platform team\nsam@acme.com\n\n  lee@acme.com  # contractor\nSam@acme.com\n";
+        assert_eq!(
+            parse_email_list(text).unwrap(),
+            vec!["sam@acme.com", "lee@acme.com"]
+        );
+        let err = parse_email_list("sam@acme.com\n7c9e6679\n").unwrap_err();
+        assert!(err.starts_with("line 2:"), "{}", err);
+    }
+}
@@ -1,15 +1,21 @@
 pub mod apps;
+pub mod cache;
+pub mod completions;
+pub mod config;
 pub mod databases;
 pub mod deployments;
 pub mod events;
+pub mod init;
 pub mod keys;
 pub mod link;
 pub mod login;
 pub mod logout;
 pub mod logs;
+pub mod ls;
 pub mod members;
 pub mod orgs;
 pub mod secrets;
+pub mod status;
 pub mod unlink;
 pub mod upgrade;
 pub mod whoami;
@@ -1,11 +1,18 @@
 use clap::{Parser, Subcommand};
+use colored::Colorize;
+use inquire::Select;
 use uuid::Uuid;
 
-use crate::api::models::CreateOrgRequest;
+use crate::api::models::{CreateOrgRequest, Organization};
+use crate::bulk;
+use crate::cache;
 use crate::client::QuomeClient;
-use crate::config::Config;
-use crate::errors::Result;
-use crate::ui::{self, OrgRow};
+use crate::commands::keys::key_rows;
+use crate::commands::members::member_rows;
+use crate::config::{Config, DefaultOrg, LinkedContext};
+use crate::context;
+use crate::errors::{QuomeError, Result};
+use crate::ui::{self, OrgRow, TimeStyle};
 
 #[derive(Subcommand)]
 pub enum OrgsCommands {
@@ -15,10 +22,20 @@ pub enum OrgsCommands {
     Create(CreateArgs),
     /// Get organization details
     Get(GetArgs),
+    /// Pick the organization used where no directory is linked
+    Switch(SwitchArgs),
 }
 
 #[derive(Parser)]
 pub struct ListArgs {
+    /// Only organizations you own or administer
+    #[arg(long)]
+    mine: bool,
+
+    /// Serve the last cached result instead of calling the API
+    #[arg(long)]
+    offline: bool,
+
     /// Output as JSON
     #[arg(long)]
     json: bool,
@@ -37,6 +54,10 @@ pub struct CreateArgs {
     #[arg(short, long)]
     description: Option<String>,
 
+    /// Link the current directory to the new organization
+    #[arg(long)]
+    link: bool,
+
     /// Output as JSON
     #[arg(long)]
     json: bool,
@@ -48,6 +69,36 @@ pub struct GetArgs {
     #[arg(short, long)]
     id: Option<Uuid>,
 
+    /// Organization name or slug, resolved via the org list
+    #[arg(long, conflicts_with = "id")]
+    org_name: Option<String>,
+
+    /// Also show the organization's members
+    #[arg(long)]
+    members: bool,
+
+    /// Also show the organization's API keys
+    #[arg(long)]
+    keys: bool,
+
+    /// Output as JSON
+    #[arg(long)]
+    json: bool,
+
+    /// Print QUOME_ORG=<id> for a .env file or $GITHUB_ENV
+    #[arg(long, conflicts_with_all = ["json", "members", "keys"])]
+    dotenv: bool,
+}
+
+#[derive(Parser)]
+pub struct SwitchArgs {
+    /// Organization ID (skips interactive selection)
+    org: Option<Uuid>,
+
+    /// Also link the current directory to the organization
+    #[arg(long)]
+    link: bool,
+
     /// Output as JSON
     #[arg(long)]
     json: bool,
@@ -58,6 +109,7 @@ pub async fn execute(command: OrgsCommands) -> Result<()> {
         OrgsCommands::List(args) => list(args).await,
         OrgsCommands::Create(args) => create(args).await,
         OrgsCommands::Get(args) => get(args).await,
+        OrgsCommands::Switch(args) => switch(args).await,
     }
 }
 
@@ -80,12 +132,52 @@ async fn list(args: ListArgs) -> Result<()> {
 
     let client = QuomeClient::new(Some(&token), None)?;
 
+    // Roles cost a request per org; only the table and --mine use them
+    let with_roles = args.mine || !ui::structured(args.json);
+
     let sp = ui::spinner("Fetching organizations...");
-    let orgs = client.list_orgs().await?;
+    let orgs: Vec<(Organization, Option<String>)> = cache::through("orgs", args.offline, async {
+        if !with_roles {
+            return Ok(client
+                .list_orgs()
+                .await?
+                .into_iter()
+                .map(|org| (org, None))
+                .collect());
+        }
+        let (orgs, me) = tokio::try_join!(client.list_orgs(), client.get_current_user())?;
+        let roles = bulk::run(orgs.iter().collect(), |org| role_in(&client, org, me.id)).await;
+        orgs.into_iter()
+            .zip(roles)
+            .map(|(org, role)| Ok((org, Some(role?))))
+            .collect()
+    })
+    .await?;
     sp.finish_and_clear();
 
-    if args.json {
-        println!("{}", serde_json::to_string_pretty(&orgs)?);
+    if args.mine && orgs.iter().any(|(_, role)| role.is_none()) {
+        return Err(QuomeError::NotFound(
+            "the cached org list has no roles; run `quome orgs list --mine` once without --offline"
+                .into(),
+        ));
+    }
+    let orgs: Vec<(Organization, Option<String>)> = orgs
+        .into_iter()
+        .filter(|(_, role)| !args.mine || matches!(role.as_deref(), Some("owner" | "admin")))
+        .collect();
+
+    if ui::structured(args.json) {
+        let values = orgs
+            .iter()
+            .map(|(org, role)| {
+                let mut value = serde_json::to_value(org)?;
+                if let Some(role) = role {
+                    value["role"] = role.as_str().into();
+                }
+                Ok(value)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        ui::print_structured(&values)?;
     } else {
         if orgs.is_empty() {
             println!("No organizations found.");
@@ -94,11 +186,12 @@ async fn list(args: ListArgs) -> Result<()> {
 
         let rows: Vec<OrgRow> = orgs
             .iter()
-            .map(|org| OrgRow {
+            .map(|(org, role)| OrgRow {
                 id: org.id.to_string(),
                 name: org.name.clone(),
                 slug: org.slug.clone(),
-                created: org.created_at.format("%Y-%m-%d %H:%M").to_string(),
+                role: role.clone().unwrap_or_else(|| "-".to_string()),
+                created: ui::fmt_time(&org.created_at, TimeStyle::List),
             })
             .collect();
 
@@ -108,8 +201,26 @@ async fn list(args: ListArgs) -> Result<()> {
     Ok(())
 }
 
+/// Your role in `org`: owners are known from the org itself, anyone else is
+/// looked up in the member list. Plain members may not list members; that
+/// 403 is what tells us they're a `member`.
+async fn role_in(client: &QuomeClient, org: &Organization, user_id: Uuid) -> Result<String> {
+    if org.owner_id == user_id {
+        return Ok("owner".to_string());
+    }
+    let members = match client.list_org_members(org.id).await {
+        Err(QuomeError::Forbidden(_)) => return Ok("member".to_string()),
+        members => members?,
+    };
+    Ok(members
+        .into_iter()
+        .find(|m| m.user_id == user_id)
+        .map(|m| m.role)
+        .unwrap_or_else(|| "member".to_string()))
+}
+
 async fn create(args: CreateArgs) -> Result<()> {
-    let config = Config::load()?;
+    let mut config = Config::load()?;
     let token = config.require_token()?;
 
     let client = QuomeClient::new(Some(&token), None)?;
@@ -126,17 +237,32 @@ async fn create(args: CreateArgs) -> Result<()> {
         .await?;
     sp.finish_and_clear();
 
-    if args.json {
-        println!("{}", serde_json::to_string_pretty(&org)?);
+    let linked_dir = if args.link {
+        config.set_linked(LinkedContext {
+            org_id: org.id,
+            org_name: org.name.clone(),
+            app_id: None,
+            app_name: None,
+        })?;
+        config.save()?;
+        Some(Config::current_dir_key()?)
     } else {
-        ui::print_success(
-            "Created organization",
-            &[
-                ("ID", &org.id.to_string()),
-                ("Name", &org.name),
-                ("Slug", &org.slug),
-            ],
-        );
+        None
+    };
+
+    if ui::structured(args.json) {
+        ui::print_structured(&org)?;
+    } else {
+        let id = org.id.to_string();
+        let mut details = vec![
+            ("ID", id.as_str()),
+            ("Name", &org.name),
+            ("Slug", &org.slug),
+        ];
+        if let Some(ref dir) = linked_dir {
+            details.push(("Linked", dir));
+        }
+        ui::print_success("Created organization", &details);
     }
 
     Ok(())
@@ -146,19 +272,40 @@ async fn get(args: GetArgs) -> Result<()> {
     let config = Config::load()?;
     let token = config.require_token()?;
 
-    let org_id = match args.id {
-        Some(id) => id,
-        None => config.require_linked_org()?,
-    };
-
     let client = QuomeClient::new(Some(&token), None)?;
 
+    let org_id = context::resolve_org(&config, &client, args.id, args.org_name.as_deref()).await?;
+
+    let members = async {
+        if args.members {
+            client.list_org_members(org_id).await.map(Some)
+        } else {
+            Ok(None)
+        }
+    };
+    let keys = async {
+        if args.keys {
+            client.list_org_keys(org_id).await.map(Some)
+        } else {
+            Ok(None)
+        }
+    };
+
     let sp = ui::spinner("Fetching organization...");
-    let org = client.get_org(org_id).await?;
+    let (org, members, keys) = tokio::try_join!(client.get_org(org_id), members, keys)?;
     sp.finish_and_clear();
 
-    if args.json {
-        println!("{}", serde_json::to_string_pretty(&org)?);
+    if args.dotenv {
+        println!("QUOME_ORG={}", org.id);
+    } else if ui::structured(args.json) {
+        let mut value = serde_json::to_value(&org)?;
+        if let Some(ref members) = members {
+            value["members"] = serde_json::to_value(members)?;
+        }
+        if let Some(ref keys) = keys {
+            value["keys"] = serde_json::to_value(keys)?;
+        }
+        ui::print_structured(&value)?;
     } else {
         let mut details = vec![
             ("ID", org.id.to_string()),
@@ -173,15 +320,116 @@ async fn get(args: GetArgs) -> Result<()> {
             details.push(("Cloud", provider.clone()));
         }
         details.push(("Cloud connected", org.gcp_connected.to_string()));
-        details.push((
-            "Created",
-            org.created_at.format("%Y-%m-%d %H:%M:%S").to_string(),
-        ));
+        details.push(("Created", ui::fmt_time(&org.created_at, TimeStyle::Detail)));
 
         let details_ref: Vec<(&str, &str)> =
             details.iter().map(|(k, v)| (*k, v.as_str())).collect();
 
         ui::print_detail(&org.name, &details_ref);
+
+        if let Some(ref members) = members {
+            println!();
+            println!("{}", "Members".bold());
+            if members.is_empty() {
+                println!("  No members found.");
+            } else {
+                ui::print_table(member_rows(members));
+            }
+        }
+        if let Some(ref keys) = keys {
+            println!();
+            println!("{}", "API keys".bold());
+            if keys.is_empty() {
+                println!("  No API keys found.");
+            } else {
+                ui::print_table(key_rows(keys));
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// How an organization appears in the `orgs switch` picker.
+fn switch_label(org: &Organization, current: Option<Uuid>) -> String {
+    let label = format!("{} ({})", org.name, org.id);
+    if current == Some(org.id) {
+        format!("{} (current)", label)
+    } else {
+        label
+    }
+}
+
+async fn switch(args: SwitchArgs) -> Result<()> {
+    let mut config = Config::load()?;
+    let token = config.require_token()?;
+
+    let client = QuomeClient::new(Some(&token), None)?;
+
+    let sp = ui::spinner("Fetching organizations...");
+    let orgs = client.list_orgs().await?;
+    sp.finish_and_clear();
+
+    let org = match args.org {
+        Some(id) => orgs
+            .iter()
+            .find(|o| o.id == id)
+            .ok_or_else(|| QuomeError::ApiError(format!("Organization {} not found", id)))?,
+        None => {
+            if orgs.is_empty() {
+                return Err(QuomeError::ApiError(
+                    "No organizations found. Create one with `quome orgs create`.".into(),
+                ));
+            }
+            let current = config.get_linked_org_id()?;
+            let options: Vec<String> = orgs.iter().map(|o| switch_label(o, current)).collect();
+            let start = orgs.iter().position(|o| Some(o.id) == current).unwrap_or(0);
+
+            let selection = Select::new("Switch to organization:", options.clone())
+                .with_starting_cursor(start)
+                .prompt()
+                .map_err(|e| QuomeError::Io(std::io::Error::other(e.to_string())))?;
+            let idx = options.iter().position(|o| *o == selection).unwrap();
+            &orgs[idx]
+        }
+    };
+
+    config.default_org = Some(DefaultOrg {
+        id: org.id,
+        name: org.name.clone(),
+    });
+    let linked_dir = if args.link {
+        config.set_linked(LinkedContext {
+            org_id: org.id,
+            org_name: org.name.clone(),
+            app_id: None,
+            app_name: None,
+        })?;
+        Some(Config::current_dir_key()?)
+    } else {
+        None
+    };
+    config.save()?;
+
+    if ui::structured(args.json) {
+        ui::print_structured(&serde_json::json!({
+            "org_id": org.id,
+            "org_name": org.name,
+            "linked": linked_dir,
+        }))?;
+    } else {
+        let id = org.id.to_string();
+        let mut details = vec![("ID", id.as_str()), ("Name", &org.name)];
+        if let Some(ref dir) = linked_dir {
+            details.push(("Linked", dir));
+        }
+        ui::print_success("Switched default organization", &details);
+        if std::env::var("QUOME_ORG").is_ok() {
+            println!(
+                "{}",
+                "Note: QUOME_ORG is set and still takes precedence.".yellow()
+            );
+        }
     }
 
     Ok(())
@@ -189,7 +437,9 @@ async fn get(args: GetArgs) -> Result<()> {
 
 #[cfg(test)]
 mod tests {
-    use super::slugify;
+    use super::{slugify, switch_label};
+    use crate::api::models::Organization;
+    use uuid::Uuid;
 
     #[test]
     fn slugify_basic() {
@@ -198,4 +448,24 @@ mod tests {
         assert_eq!(slugify("already-slugged"), "already-slugged");
         assert_eq!(slugify("Multiple   Spaces"), "multiple-spaces");
     }
+
+    #[test]
+    fn switch_label_marks_current_org() {
+        let org: Organization = serde_json::from_value(serde_json::json!({
+            "id": "0d9f4a3b-0000-4000-8000-000000000001",
+            "name": "Acme",
+            "slug": "acme",
+            "owner_id": "0d9f4a3b-0000-4000-8000-000000000002",
+            "created_at": "2024-01-01T00:00:00Z",
+            "updated_at": "2024-01-01T00:00:00Z",
+        }))
+        .unwrap();
+        let label = "Acme (0d9f4a3b-0000-4000-8000-000000000001)";
+        assert_eq!(switch_label(&org, None), label);
+        assert_eq!(switch_label(&org, Some(Uuid::nil())), label);
+        assert_eq!(
+            switch_label(&org, Some(org.id)),
+            format!("{} (current)", label)
+        );
+    }
 }
@@ -1,11 +1,19 @@
-use clap::{Parser, Subcommand};
+use clap::{ArgGroup, Parser, Subcommand};
+use colored::Colorize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::fs;
+use std::path::PathBuf;
 use uuid::Uuid;
 
-use crate::api::models::{CreateSecretRequest, UpdateSecretRequest};
+use crate::api::models::{CreateSecretRequest, Secret, UpdateSecretRequest};
+use crate::bulk;
+use crate::cache;
 use crate::client::QuomeClient;
 use crate::config::Config;
-use crate::errors::Result;
-use crate::ui::{self, SecretRow};
+use crate::context::{self, OrgArgs};
+use crate::errors::{QuomeError, Result};
+use crate::ui::{self, SecretRow, TimeStyle};
+use crate::util;
 
 #[derive(Subcommand)]
 pub enum SecretsCommands {
@@ -17,13 +25,18 @@ pub enum SecretsCommands {
     Get(GetArgs),
     /// Delete a secret
     Delete(DeleteArgs),
+    /// Compare secret names (and optionally values) between two orgs or an env file
+    Diff(DiffArgs),
 }
 
 #[derive(Parser)]
 pub struct ListArgs {
-    /// Organization ID (uses linked org if not provided)
+    #[command(flatten)]
+    org: OrgArgs,
+
+    /// Serve the last cached result instead of calling the API
     #[arg(long)]
-    org: Option<Uuid>,
+    offline: bool,
 
     /// Output as JSON
     #[arg(long)]
@@ -33,18 +46,51 @@ pub struct ListArgs {
 #[derive(Parser)]
 pub struct SetArgs {
     /// Secret name
-    name: String,
+    #[arg(required_unless_present = "from_env")]
+    name: Option<String>,
 
     /// Secret value
-    value: String,
+    #[arg(required_unless_present = "from_env")]
+    value: Option<String>,
 
-    /// Secret description
+    /// Secret description ("-" reads it from stdin)
     #[arg(short, long)]
     description: Option<String>,
 
-    /// Organization ID (uses linked org if not provided)
+    /// Read the description from a file
+    #[arg(long, value_name = "PATH", conflicts_with = "description")]
+    description_from_file: Option<PathBuf>,
+
+    /// Only create: leave an existing secret untouched
+    #[arg(long, conflicts_with = "if_exists")]
+    if_not_exists: bool,
+
+    /// Only update: skip if the secret doesn't exist yet
     #[arg(long)]
-    org: Option<Uuid>,
+    if_exists: bool,
+
+    /// Set every environment variable starting with --env-prefix, prefix stripped
+    #[arg(
+        long,
+        requires = "env_prefix",
+        conflicts_with_all = ["name", "value", "description", "description_from_file"]
+    )]
+    from_env: bool,
+
+    /// Prefix selecting the variables for --from-env (e.g. APP_)
+    #[arg(long, value_name = "PREFIX", requires = "from_env")]
+    env_prefix: Option<String>,
+
+    /// Skip the confirmation prompt (with --from-env)
+    #[arg(short, long, requires = "from_env")]
+    yes: bool,
+
+    /// Stop at the first failure instead of trying every secret (with --from-env)
+    #[arg(long, requires = "from_env")]
+    fail_fast: bool,
+
+    #[command(flatten)]
+    org: OrgArgs,
 
     /// Output as JSON
     #[arg(long)]
@@ -56,27 +102,94 @@ pub struct GetArgs {
     /// Secret name
     name: String,
 
-    /// Organization ID (uses linked org if not provided)
-    #[arg(long)]
-    org: Option<Uuid>,
+    #[command(flatten)]
+    org: OrgArgs,
 
     /// Output as JSON
     #[arg(long)]
     json: bool,
+
+    /// Print only the value, without a trailing newline
+    #[arg(long, conflicts_with = "json")]
+    raw: bool,
+
+    /// Write the value to this file (mode 0600) instead of printing it
+    #[arg(long, value_name = "PATH", conflicts_with_all = ["json", "raw"])]
+    output_file: Option<PathBuf>,
+
+    /// Overwrite --output-file if it already exists
+    #[arg(short, long, requires = "output_file")]
+    force: bool,
 }
 
 #[derive(Parser)]
 pub struct DeleteArgs {
     /// Secret name
-    name: String,
+    #[arg(required_unless_present = "all", conflicts_with = "all")]
+    name: Option<String>,
 
-    /// Organization ID (uses linked org if not provided)
+    /// Delete every secret matching --filter
     #[arg(long)]
-    org: Option<Uuid>,
+    all: bool,
+
+    /// Only delete secrets whose name contains this substring (with --all)
+    #[arg(long, requires = "all")]
+    filter: Option<String>,
+
+    /// Allow --all without --filter (deletes every secret in the org)
+    #[arg(long, requires = "all")]
+    really_all: bool,
+
+    /// Stop starting new deletions after the first failure (with --all)
+    #[arg(long, requires = "all")]
+    fail_fast: bool,
+
+    #[command(flatten)]
+    org: OrgArgs,
 
     /// Skip confirmation prompt
     #[arg(short, long)]
     force: bool,
+
+    /// Output the batch result as JSON (with --all)
+    #[arg(long, requires = "all")]
+    json: bool,
+}
+
+#[derive(Parser)]
+#[command(group(
+    ArgGroup::new("source")
+        .required(true)
+        .args(["from_org", "from_org_name", "file"])
+))]
+pub struct DiffArgs {
+    /// Organization to compare from
+    #[arg(long)]
+    from_org: Option<Uuid>,
+
+    /// Organization to compare from, by name or slug
+    #[arg(long)]
+    from_org_name: Option<String>,
+
+    /// .env file to compare from
+    #[arg(long)]
+    file: Option<PathBuf>,
+
+    /// Organization to compare to (uses linked org if not provided)
+    #[arg(long, alias = "org")]
+    to_org: Option<Uuid>,
+
+    /// Organization to compare to, by name or slug
+    #[arg(long, alias = "org-name", conflicts_with = "to_org")]
+    to_org_name: Option<String>,
+
+    /// Also compare values of secrets present on both sides (values are never printed)
+    #[arg(long)]
+    reveal: bool,
+
+    /// Output as JSON
+    #[arg(long)]
+    json: bool,
 }
 
 pub async fn execute(command: SecretsCommands) -> Result<()> {
@@ -85,39 +198,76 @@ pub async fn execute(command: SecretsCommands) -> Result<()> {
         SecretsCommands::Set(args) => set(args).await,
         SecretsCommands::Get(args) => get(args).await,
         SecretsCommands::Delete(args) => delete(args).await,
+        SecretsCommands::Diff(args) => diff(args).await,
     }
 }
 
+/// Parse `KEY=VALUE` lines from a .env file, skipping blanks and comments and
+/// accepting an `export ` prefix and single- or double-quoted values.
+fn parse_env_file(content: &str) -> BTreeMap<String, String> {
+    content
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with('#'))
+        .filter_map(|line| {
+            let line = line.strip_prefix("export ").unwrap_or(line);
+            let (key, value) = line.split_once('=')?;
+            let value = value.trim();
+            let value = value
+                .strip_prefix('"')
+                .and_then(|v| v.strip_suffix('"'))
+                .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+                .unwrap_or(value);
+            Some((key.trim().to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Variables whose name starts with `prefix`, keyed by the rest of the name.
+/// A variable named exactly `prefix` has nothing left to name a secret by.
+fn env_secrets(
+    vars: impl IntoIterator<Item = (String, String)>,
+    prefix: &str,
+) -> BTreeMap<String, String> {
+    vars.into_iter()
+        .filter_map(|(key, value)| {
+            let name = key.strip_prefix(prefix)?;
+            (!name.is_empty()).then(|| (name.to_string(), value))
+        })
+        .collect()
+}
+
 async fn list(args: ListArgs) -> Result<()> {
     let config = Config::load()?;
     let token = config.require_token()?;
-
-    let org_id = match args.org {
-        Some(id) => id,
-        None => config.require_linked_org()?,
-    };
-
     let client = QuomeClient::new(Some(&token), None)?;
 
+    let org_id = args
+        .org
+        .resolve_cached(&config, &client, args.offline)
+        .await?;
+
     let sp = ui::spinner("Fetching secrets...");
-    let response = client.list_secrets(org_id).await?;
+    let secrets = cache::through(&format!("secrets-{}", org_id), args.offline, async {
+        Ok(client.list_secrets(org_id).await?.data)
+    })
+    .await?;
     sp.finish_and_clear();
 
-    if args.json {
-        println!("{}", serde_json::to_string_pretty(&response.data)?);
+    if ui::structured(args.json) {
+        ui::print_structured(&secrets)?;
     } else {
-        if response.data.is_empty() {
+        if secrets.is_empty() {
             println!("No secrets found.");
             return Ok(());
         }
 
-        let rows: Vec<SecretRow> = response
-            .data
+        let rows: Vec<SecretRow> = secrets
             .iter()
             .map(|secret| SecretRow {
                 name: secret.name.clone(),
                 id: secret.id.to_string(),
-                updated: secret.updated_at.format("%Y-%m-%d %H:%M").to_string(),
+                updated: ui::fmt_time(&secret.updated_at, TimeStyle::List),
             })
             .collect();
 
@@ -127,23 +277,51 @@ async fn list(args: ListArgs) -> Result<()> {
     Ok(())
 }
 
-async fn set(args: SetArgs) -> Result<()> {
-    let config = Config::load()?;
-    let token = config.require_token()?;
-
-    let org_id = match args.org {
-        Some(id) => id,
-        None => config.require_linked_org()?,
+async fn set(mut args: SetArgs) -> Result<()> {
+    if args.from_env {
+        return set_from_env(args).await;
+    }
+    args.description = read_description(&args)?;
+    let (Some(name), Some(value)) = (args.name, args.value) else {
+        unreachable!("clap requires NAME and VALUE without --from-env")
     };
 
+    let config = Config::load()?;
+    let token = config.require_token()?;
     let client = QuomeClient::new(Some(&token), None)?;
 
+    let org_id = args.org.resolve(&config, &client).await?;
+
     // Check if secret exists
     let sp = ui::spinner("Checking for existing secret...");
     let response = client.list_secrets(org_id).await?;
-    let existing = response.data.iter().find(|s| s.name == args.name);
+    let existing = response.data.iter().find(|s| s.name == name);
     sp.finish_and_clear();
 
+    let skip_reason = match existing {
+        Some(_) if args.if_not_exists => Some("already exists"),
+        None if args.if_exists => Some("does not exist"),
+        _ => None,
+    };
+    if let Some(reason) = skip_reason {
+        if ui::structured(args.json) {
+            let mut value = match existing {
+                Some(secret) => serde_json::to_value(secret)?,
+                None => serde_json::json!({ "name": name }),
+            };
+            value["action"] = "skipped".into();
+            ui::print_structured(&value)?;
+        } else {
+            println!(
+                "{} Skipped secret {} ({})",
+                "-".dimmed(),
+                name.bold(),
+                reason
+            );
+        }
+        return Ok(());
+    }
+
     let (secret, action) = if let Some(existing_secret) = existing {
         // Update existing secret
         let sp = ui::spinner("Updating secret...");
@@ -152,7 +330,7 @@ async fn set(args: SetArgs) -> Result<()> {
                 org_id,
                 existing_secret.id,
                 &UpdateSecretRequest {
-                    value: Some(args.value),
+                    value: Some(value),
                     description: args.description,
                 },
             )
@@ -166,8 +344,8 @@ async fn set(args: SetArgs) -> Result<()> {
             .create_secret(
                 org_id,
                 &CreateSecretRequest {
-                    name: args.name,
-                    value: args.value,
+                    name,
+                    value,
                     description: args.description,
                 },
             )
@@ -176,8 +354,10 @@ async fn set(args: SetArgs) -> Result<()> {
         (secret, "Created")
     };
 
-    if args.json {
-        println!("{}", serde_json::to_string_pretty(&secret)?);
+    if ui::structured(args.json) {
+        let mut value = serde_json::to_value(&secret)?;
+        value["action"] = action.to_lowercase().into();
+        ui::print_structured(&value)?;
     } else {
         ui::print_success(
             &format!("{} secret", action),
@@ -188,29 +368,159 @@ async fn set(args: SetArgs) -> Result<()> {
     Ok(())
 }
 
-async fn get(args: GetArgs) -> Result<()> {
+async fn set_from_env(args: SetArgs) -> Result<()> {
+    let prefix = args.env_prefix.as_deref().unwrap_or_default();
+    let vars = env_secrets(std::env::vars(), prefix);
+    if vars.is_empty() {
+        return Err(QuomeError::ApiError(format!(
+            "No environment variables start with {}",
+            prefix
+        )));
+    }
+    for value in vars.values() {
+        util::register_sensitive(value);
+    }
+
     let config = Config::load()?;
     let token = config.require_token()?;
+    let client = QuomeClient::new(Some(&token), None)?;
+
+    let org_id = args.org.resolve(&config, &client).await?;
+
+    let sp = ui::spinner("Checking for existing secrets...");
+    let existing: BTreeMap<String, Uuid> = client
+        .list_secrets(org_id)
+        .await?
+        .data
+        .into_iter()
+        .map(|s| (s.name, s.id))
+        .collect();
+    sp.finish_and_clear();
 
-    let org_id = match args.org {
-        Some(id) => id,
-        None => config.require_linked_org()?,
+    let captured: Vec<String> = vars
+        .iter()
+        .map(|(name, value)| {
+            let verb = if existing.contains_key(name) {
+                "update"
+            } else {
+                "create"
+            };
+            format!(
+                "{} = **** ({} chars, {})",
+                name,
+                value.chars().count(),
+                verb
+            )
+        })
+        .collect();
+    if !bulk::confirm("Set", "secrets", &captured, args.yes)? {
+        println!("Cancelled.");
+        return Ok(());
+    }
+
+    let mut skipped = Vec::new();
+    let mut items = Vec::new();
+    for (name, value) in vars {
+        let id = existing.get(&name).copied();
+        match id {
+            Some(_) if args.if_not_exists => skipped.push((name, "already exists")),
+            None if args.if_exists => skipped.push((name, "does not exist")),
+            _ => items.push((name.clone(), (name, value, id))),
+        }
+    }
+
+    let mut batch = bulk::run_batch(
+        "Set",
+        items,
+        args.fail_fast,
+        |(name, value, id): (String, String, Option<Uuid>)| {
+            let client = &client;
+            async move {
+                match id {
+                    Some(id) => {
+                        let request = UpdateSecretRequest {
+                            value: Some(value),
+                            description: None,
+                        };
+                        client.update_secret(org_id, id, &request).await
+                    }
+                    None => {
+                        let request = CreateSecretRequest {
+                            name,
+                            value,
+                            description: None,
+                        };
+                        client.create_secret(org_id, &request).await
+                    }
+                }
+            }
+        },
+    )
+    .await;
+    for (name, reason) in skipped {
+        batch.skip(name, reason);
+    }
+    batch.report(ui::structured(args.json))
+}
+
+/// The description as given, from stdin (`-`) or from
+/// `--description-from-file`, with trailing whitespace trimmed.
+fn read_description(args: &SetArgs) -> Result<Option<String>> {
+    let text = match (args.description.as_deref(), &args.description_from_file) {
+        (Some("-"), _) => std::io::read_to_string(std::io::stdin())?,
+        (Some(text), _) => text.to_string(),
+        (None, Some(path)) => fs::read_to_string(path)?,
+        (None, None) => return Ok(None),
     };
+    Ok(Some(text.trim_end().to_string()))
+}
 
+async fn get(args: GetArgs) -> Result<()> {
+    let config = Config::load()?;
+    let token = config.require_token()?;
     let client = QuomeClient::new(Some(&token), None)?;
 
+    let org_id = args.org.resolve(&config, &client).await?;
+
+    let exists = |path: &std::path::Path| {
+        QuomeError::ApiError(format!(
+            "{} already exists. Pass --force to overwrite it.",
+            path.display()
+        ))
+    };
+
+    // Fast path so a refusal usually never pulls the value; the write below
+    // is what actually guarantees nothing gets overwritten
+    if let Some(ref path) = args.output_file {
+        if !args.force && path.symlink_metadata().is_ok() {
+            return Err(exists(path));
+        }
+    }
+
     let sp = ui::spinner("Fetching secret...");
     let secret = client.get_secret_value(org_id, &args.name).await?;
     sp.finish_and_clear();
 
-    if args.json {
-        println!(
-            "{}",
-            serde_json::to_string_pretty(&serde_json::json!({
-                "name": args.name,
-                "value": secret.value,
-            }))?
+    if let Some(ref path) = args.output_file {
+        util::write_private(path, secret.value.as_bytes(), args.force).map_err(|e| match e {
+            QuomeError::Io(e) if e.kind() == std::io::ErrorKind::AlreadyExists => exists(path),
+            e => e,
+        })?;
+        ui::print_success(
+            "Wrote secret",
+            &[
+                ("Name", &args.name),
+                ("File", &path.display().to_string()),
+                ("Size", &format!("{} bytes", secret.value.len())),
+            ],
         );
+    } else if ui::structured(args.json) {
+        ui::print_structured(&serde_json::json!({
+            "name": args.name,
+            "value": secret.value,
+        }))?;
+    } else if args.raw {
+        ui::print_raw(&secret.value)?;
     } else {
         println!("{}", secret.value);
     }
@@ -221,16 +531,18 @@ async fn get(args: GetArgs) -> Result<()> {
 async fn delete(args: DeleteArgs) -> Result<()> {
     let config = Config::load()?;
     let token = config.require_token()?;
+    let client = QuomeClient::new(Some(&token), None)?;
+
+    let org_id = args.org.resolve(&config, &client).await?;
 
-    let org_id = match args.org {
-        Some(id) => id,
-        None => config.require_linked_org()?,
+    let Some(name) = args.name.clone() else {
+        return delete_all(args, org_id, &client).await;
     };
 
     if !args.force {
         let confirm = inquire::Confirm::new(&format!(
             "Are you sure you want to delete secret '{}'?",
-            args.name
+            name
         ))
         .with_default(false)
         .prompt()
@@ -242,23 +554,218 @@ async fn delete(args: DeleteArgs) -> Result<()> {
         }
     }
 
-    let client = QuomeClient::new(Some(&token), None)?;
-
     // Find secret by name
     let sp = ui::spinner("Fetching secret...");
     let response = client.list_secrets(org_id).await?;
     let secret = response
         .data
         .iter()
-        .find(|s| s.name == args.name)
-        .ok_or_else(|| crate::errors::QuomeError::NotFound(format!("Secret '{}'", args.name)))?;
+        .find(|s| s.name == name)
+        .ok_or_else(|| crate::errors::QuomeError::NotFound(format!("Secret '{}'", name)))?;
     sp.finish_and_clear();
 
     let sp = ui::spinner("Deleting secret...");
     client.delete_secret(org_id, secret.id).await?;
     sp.finish_and_clear();
 
-    ui::print_success("Deleted secret", &[("Name", &args.name)]);
+    ui::print_success("Deleted secret", &[("Name", &name)]);
+
+    Ok(())
+}
+
+async fn delete_all(args: DeleteArgs, org_id: Uuid, client: &QuomeClient) -> Result<()> {
+    let sp = ui::spinner("Fetching secrets...");
+    let response = client.list_secrets(org_id).await?;
+    sp.finish_and_clear();
+
+    let secrets = bulk::select(
+        response.data,
+        |s| &s.name,
+        args.filter.as_deref(),
+        args.really_all,
+        "secret",
+    )?;
+
+    if secrets.is_empty() {
+        println!("No matching secrets found.");
+        return Ok(());
+    }
+
+    let names: Vec<String> = secrets.iter().map(|s| s.name.clone()).collect();
+
+    if !bulk::confirm("Delete", "secrets", &names, args.force)? {
+        println!("Cancelled.");
+        return Ok(());
+    }
+
+    let items = names
+        .into_iter()
+        .zip(secrets.iter().map(|s| s.id))
+        .collect();
+    bulk::run_batch("Deleted", items, args.fail_fast, |id| {
+        client.delete_secret(org_id, id)
+    })
+    .await
+    .report(ui::structured(args.json))
+}
+
+async fn diff(args: DiffArgs) -> Result<()> {
+    let config = Config::load()?;
+    let token = config.require_token()?;
+
+    let client = QuomeClient::new(Some(&token), None)?;
+
+    let to_org =
+        context::resolve_org(&config, &client, args.to_org, args.to_org_name.as_deref()).await?;
+
+    let source = match args.file {
+        Some(path) => {
+            let values = parse_env_file(&fs::read_to_string(&path)?);
+            DiffSource::File(path, values)
+        }
+        None => DiffSource::Org(
+            context::resolve_org(
+                &config,
+                &client,
+                args.from_org,
+                args.from_org_name.as_deref(),
+            )
+            .await?,
+        ),
+    };
+
+    let from_names = async {
+        match source {
+            DiffSource::File(_, ref values) => Ok(values.keys().cloned().collect::<BTreeSet<_>>()),
+            DiffSource::Org(org) => Ok(names(client.list_secrets(org).await?.data)),
+        }
+    };
+
+    let sp = ui::spinner("Fetching secrets...");
+    let (from_names, to_secrets) = tokio::try_join!(from_names, client.list_secrets(to_org))?;
+    sp.finish_and_clear();
+    let to_names = names(to_secrets.data);
+
+    let only_from: Vec<&String> = from_names.difference(&to_names).collect();
+    let only_to: Vec<&String> = to_names.difference(&from_names).collect();
+    let both: Vec<&String> = from_names.intersection(&to_names).collect();
+
+    let differing: Option<BTreeSet<&String>> = if args.reveal {
+        let sp = ui::spinner("Comparing values...");
+        let (client, source) = (&client, &source);
+        let results = bulk::run(both.clone(), |name| async move {
+            let to = client.get_secret_value(to_org, name).await?.value;
+            let from = match source {
+                DiffSource::File(_, values) => values[name].clone(),
+                DiffSource::Org(org) => client.get_secret_value(*org, name).await?.value,
+            };
+            Ok(from != to)
+        })
+        .await;
+        sp.finish_and_clear();
+
+        let mut differing = BTreeSet::new();
+        for (name, result) in both.iter().zip(results) {
+            if result? {
+                differing.insert(*name);
+            }
+        }
+        Some(differing)
+    } else {
+        None
+    };
+
+    if ui::structured(args.json) {
+        let mut value = serde_json::json!({
+            "only_in_from": only_from,
+            "only_in_to": only_to,
+            "in_both": both,
+        });
+        if let Some(ref differing) = differing {
+            value["differing"] = serde_json::to_value(differing)?;
+        }
+        ui::print_structured(&value)?;
+        return Ok(());
+    }
+
+    let from_label = match source {
+        DiffSource::File(ref path, _) => path.display().to_string(),
+        DiffSource::Org(org) => org.to_string(),
+    };
+
+    println!(
+        "{}",
+        format!("Only in {} ({})", from_label, only_from.len()).bold()
+    );
+    for name in &only_from {
+        println!("  {} {}", "+".green(), name);
+    }
+    println!();
+    println!(
+        "{}",
+        format!("Only in {} ({})", to_org, only_to.len()).bold()
+    );
+    for name in &only_to {
+        println!("  {} {}", "-".red(), name);
+    }
+    println!();
+    println!("{}", format!("In both ({})", both.len()).bold());
+    for name in &both {
+        match differing {
+            Some(ref d) if d.contains(name) => {
+                println!("  {} {} {}", "~".yellow(), name, "differs".yellow())
+            }
+            Some(_) => println!("  {} {} {}", "=".dimmed(), name, "same".dimmed()),
+            None => println!("  {} {}", "=".dimmed(), name),
+        }
+    }
 
     Ok(())
 }
+
+/// The side of a `secrets diff` compared against the target org.
+enum DiffSource {
+    File(PathBuf, BTreeMap<String, String>),
+    Org(Uuid),
+}
+
+fn names(secrets: Vec<Secret>) -> BTreeSet<String> {
+    secrets.into_iter().map(|s| s.name).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{env_secrets, parse_env_file};
+
+    #[test]
+    fn parse_env_file_handles_comments_quotes_and_export() {
+        let parsed = parse_env_file(
+            "# comment\n\nexport API_KEY=abc\nDATABASE_URL=\"postgres://u:p@h/db\"\nNAME='a b'\nEMPTY=\nnot a pair\n",
+        );
+        assert_eq!(parsed["API_KEY"], "abc");
+        assert_eq!(parsed["DATABASE_URL"], "postgres://u:p@h/db");
+        assert_eq!(parsed["NAME"], "a b");
+        assert_eq!(parsed["EMPTY"], "");
+        assert_eq!(parsed.len(), 4);
+    }
+
+    #[test]
+    fn env_secrets_strips_the_prefix() {
+        let vars = [
+            ("APP_DATABASE_URL", "postgres://h/db"),
+            ("APP_", "nameless"),
+            ("HOME", "/root"),
+            ("app_lower", "x"),
+            ("APP_API_KEY", "abc"),
+        ]
+        .map(|(k, v)| (k.to_string(), v.to_string()));
+        let secrets = env_secrets(vars, "APP_");
+        assert_eq!(
+            secrets.into_iter().collect::<Vec<_>>(),
+            [
+                ("API_KEY".to_string(), "abc".to_string()),
+                ("DATABASE_URL".to_string(), "postgres://h/db".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,108 @@
+use clap::Parser;
+use colored::Colorize;
+
+use crate::commands::upgrade;
+use crate::config::Config;
+use crate::errors::Result;
+use crate::settings::Settings;
+use crate::ui;
+
+/// Set to skip `--check-update` everywhere (e.g. in CI images).
+const NO_UPDATE_CHECK_ENV: &str = "QUOME_NO_UPDATE_CHECK";
+
+#[derive(Parser)]
+pub struct Args {
+    /// Also report whether a newer quome is available (checked once a day)
+    #[arg(long)]
+    check_update: bool,
+
+    /// Output as JSON
+    #[arg(long)]
+    json: bool,
+}
+
+pub async fn execute(args: Args) -> Result<()> {
+    let config = Config::load()?;
+    let settings = Settings::load()?;
+    let current = env!("CARGO_PKG_VERSION");
+
+    let login = match (&config.user, config.get_token_string()) {
+        (Some(user), _) => Some(user.email.clone()),
+        (None, Some(_)) => Some("QUOME_TOKEN".to_string()),
+        (None, None) => None,
+    };
+    let linked = config.get_linked()?;
+    // Only consulted when the directory isn't linked and QUOME_ORG is unset
+    let default_org = config
+        .default_org
+        .as_ref()
+        .filter(|_| linked.is_none() && std::env::var_os("QUOME_ORG").is_none());
+
+    let latest = if args.check_update && std::env::var_os(NO_UPDATE_CHECK_ENV).is_none() {
+        let sp = ui::spinner("Checking for updates...");
+        let latest = upgrade::latest_version_cached();
+        sp.finish_and_clear();
+        // A failed check shouldn't fail `status`
+        Some(latest.ok().flatten())
+    } else {
+        None
+    };
+    let update_available = latest
+        .as_ref()
+        .map(|l| l.as_deref().is_some_and(|l| upgrade::is_newer(l, current)));
+
+    if ui::structured(args.json) {
+        ui::print_structured(&serde_json::json!({
+            "version": current,
+            "api_url": settings.get_api_url(),
+            "logged_in_as": login,
+            "organization": linked.map(|l| &l.org_name).or(default_org.map(|d| &d.name)),
+            "application": linked.and_then(|l| l.app_name.as_ref()),
+            "latest_version": latest.clone().flatten(),
+            "update_available": update_available,
+        }))?;
+        return Ok(());
+    }
+
+    let mut details = vec![
+        ("Version", current.to_string()),
+        ("API", settings.get_api_url()),
+        (
+            "Logged in as",
+            login.unwrap_or_else(|| "not logged in".dimmed().to_string()),
+        ),
+    ];
+    match linked {
+        Some(linked) => {
+            details.push(("Organization", linked.org_name.clone()));
+            if let Some(ref app) = linked.app_name {
+                details.push(("Application", app.clone()));
+            }
+        }
+        None => {
+            if let Some(org) = default_org {
+                details.push((
+                    "Organization",
+                    format!("{} {}", org.name, "(default)".dimmed()),
+                ));
+            }
+            details.push(("Linked", "no".dimmed().to_string()));
+        }
+    }
+    match (latest.flatten(), update_available) {
+        (Some(latest), Some(true)) => details.push((
+            "Update",
+            format!("{} available — run `quome upgrade`", latest)
+                .yellow()
+                .to_string(),
+        )),
+        (Some(_), _) => details.push(("Update", "up to date".green().to_string())),
+        (None, Some(_)) => details.push(("Update", "couldn't check".dimmed().to_string())),
+        (None, None) => {}
+    }
+
+    let details_ref: Vec<(&str, &str)> = details.iter().map(|(k, v)| (*k, v.as_str())).collect();
+    ui::print_detail("quome", &details_ref);
+
+    Ok(())
+}
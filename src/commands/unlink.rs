@@ -1,15 +1,45 @@
 use clap::Parser;
 use colored::Colorize;
+use inquire::Confirm;
+use std::io::IsTerminal;
 
+use crate::bulk;
 use crate::config::Config;
-use crate::errors::Result;
+use crate::errors::{QuomeError, Result};
 
 #[derive(Parser)]
-pub struct Args {}
+pub struct Args {
+    /// Remove the link of every directory, not just the current one
+    #[arg(long, conflicts_with = "prune")]
+    all: bool,
 
-pub async fn execute(_args: Args) -> Result<()> {
+    /// Remove links whose directories no longer exist
+    #[arg(long)]
+    prune: bool,
+
+    /// Skip confirmation prompt (with --all or --prune)
+    #[arg(short, long)]
+    force: bool,
+}
+
+pub async fn execute(args: Args) -> Result<()> {
     let mut config = Config::load()?;
 
+    if args.all {
+        let mut dirs: Vec<String> = config.linked.keys().cloned().collect();
+        dirs.sort();
+        return remove_links(&mut config, dirs, "links", args.force);
+    }
+    if args.prune {
+        let stale = config.stale_links();
+        return remove_links(
+            &mut config,
+            stale,
+            "links to missing directories",
+            args.force,
+        );
+    }
+
     if config.get_linked()?.is_none() {
         println!("Not linked to any organization or application.");
         return Ok(());
@@ -20,5 +50,53 @@ pub async fn execute(_args: Args) -> Result<()> {
 
     println!("{} Unlinked current directory.", "Success!".green().bold());
 
+    offer_prune(&mut config)
+}
+
+fn remove_links(config: &mut Config, dirs: Vec<String>, kind: &str, force: bool) -> Result<()> {
+    if dirs.is_empty() {
+        println!("No {} to remove.", kind);
+        return Ok(());
+    }
+    if !bulk::confirm("Remove", kind, &dirs, force)? {
+        println!("Cancelled.");
+        return Ok(());
+    }
+
+    for dir in &dirs {
+        config.linked.remove(dir);
+    }
+    config.save()?;
+
+    println!(
+        "{} Removed {} {}.",
+        "Success!".green().bold(),
+        dirs.len(),
+        kind
+    );
+    Ok(())
+}
+
+/// After a plain unlink, offer to drop links left behind by deleted projects.
+fn offer_prune(config: &mut Config) -> Result<()> {
+    let stale = config.stale_links();
+    if stale.is_empty() || !std::io::stdin().is_terminal() {
+        return Ok(());
+    }
+
+    let prune = Confirm::new(&format!(
+        "{} other linked directories no longer exist. Remove their links too?",
+        stale.len()
+    ))
+    .with_default(true)
+    .prompt()
+    .map_err(|e| QuomeError::Io(std::io::Error::other(e.to_string())))?;
+    if prune {
+        for dir in &stale {
+            config.linked.remove(dir);
+        }
+        config.save()?;
+        println!("Removed {} stale links.", stale.len());
+    }
     Ok(())
 }
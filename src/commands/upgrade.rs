@@ -1,57 +1,50 @@
+use clap::Parser;
 use colored::Colorize;
+use std::path::Path;
 use std::process::Command;
+use std::time::Duration;
 
+use crate::cache;
 use crate::errors::{QuomeError, Result};
 use crate::ui;
 
-pub async fn execute() -> Result<()> {
-    // Check if brew is available
-    let brew_check = Command::new("brew").arg("--version").output();
+/// Cache entry holding the newest released version, for `status --check-update`.
+const LATEST_ENTRY: &str = "latest-version.json";
 
-    if brew_check.is_err() {
-        return Err(QuomeError::ApiError(
-            "Homebrew not found. Please install quome manually or install Homebrew first.".into(),
-        ));
-    }
+/// How long a remembered latest version is trusted.
+const LATEST_MAX_AGE: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// Where tagged release binaries are published.
+const RELEASES_URL: &str = "https://github.com/quome-cloud/quome-cli/releases/download";
+
+#[derive(Parser)]
+pub struct Args {
+    /// Install this exact release instead of the latest, e.g. 0.2.1
+    #[arg(long = "version", value_name = "X.Y.Z")]
+    pin: Option<String>,
+}
 
+pub async fn execute(args: Args) -> Result<()> {
     // Get current version
     let current_version = env!("CARGO_PKG_VERSION");
+    if let Some(ref pin) = args.pin {
+        return install_version(pin, current_version).await;
+    }
     println!("  {} {}", "Current version:".dimmed(), current_version);
 
     let sp = ui::spinner("Checking for updates...");
-
-    // Update brew to get latest formula info
-    let update = Command::new("brew").arg("update").output()?;
-    if !update.status.success() {
-        sp.finish_and_clear();
-        let stderr = String::from_utf8_lossy(&update.stderr);
-        return Err(QuomeError::ApiError(format!(
-            "brew update failed: {}",
-            stderr
-        )));
-    }
-
-    // Check what version is available
-    let info = Command::new("brew")
-        .args(["info", "quome-cloud/quome/quome", "--json=v2"])
-        .output()?;
+    let latest_version = latest_version();
     sp.finish_and_clear();
-
-    let latest_version = if info.status.success() {
-        let json: serde_json::Value = serde_json::from_slice(&info.stdout)?;
-        json["formulae"]
-            .get(0)
-            .and_then(|f| f["versions"]["stable"].as_str())
-            .map(|s| s.to_string())
-    } else {
-        None
-    };
+    let latest_version = latest_version?;
 
     let latest = latest_version.as_deref().unwrap_or("unknown");
     println!("  {} {}", "Latest version:".dimmed(), latest);
 
     // Check if upgrade is needed
-    if latest == current_version {
+    if latest_version
+        .as_deref()
+        .is_some_and(|l| !is_newer(l, current_version))
+    {
         println!();
         println!("{} quome is already up to date", "✓".green());
         return Ok(());
@@ -93,3 +86,242 @@ pub async fn execute() -> Result<()> {
 
     Ok(())
 }
+
+/// Download the release binary for `version` and put it in place of the
+/// running one. Release binaries are macOS-only, like the Homebrew tap.
+async fn install_version(version: &str, current: &str) -> Result<()> {
+    let version = parse_version(version)?;
+    println!("  {} {}", "Current version:".dimmed(), current);
+    println!("  {} {}", "Requested version:".dimmed(), version);
+
+    if !is_newer(&version, current) && !is_newer(current, &version) {
+        println!();
+        println!("{} quome {} is already installed", "✓".green(), current);
+        return Ok(());
+    }
+
+    let artifact = release_artifact(std::env::consts::OS, std::env::consts::ARCH).ok_or_else(|| {
+        QuomeError::ApiError(format!(
+            "No release binaries for this platform. Install {} with: cargo install --git https://github.com/quome-cloud/quome-cli.git --tag v{} --force",
+            version, version
+        ))
+    })?;
+    let url = release_url(&version, artifact);
+
+    let http = reqwest::Client::builder()
+        .user_agent(concat!("quome-cli/", env!("CARGO_PKG_VERSION")))
+        .build()?;
+
+    let sp = ui::spinner(&format!("Checking release v{}...", version));
+    let found = http.head(&url).send().await;
+    sp.finish_and_clear();
+    if !found?.status().is_success() {
+        return Err(QuomeError::NotFound(format!("quome release v{}", version)));
+    }
+
+    println!();
+    let verb = if is_newer(&version, current) {
+        "Upgrade"
+    } else {
+        "Downgrade"
+    };
+    let confirm = inquire::Confirm::new(&format!("{} from {} to {}?", verb, current, version))
+        .with_default(verb == "Upgrade")
+        .prompt()
+        .map_err(|e| QuomeError::Io(std::io::Error::other(e.to_string())))?;
+    if !confirm {
+        println!("{} cancelled.", verb);
+        return Ok(());
+    }
+
+    let dir = std::env::temp_dir().join(format!("quome-upgrade-{}", std::process::id()));
+    std::fs::create_dir_all(&dir)?;
+    let result = download_and_replace(&http, &url, artifact, &dir).await;
+    let _ = std::fs::remove_dir_all(&dir);
+    let installed = result?;
+
+    println!("{} Now running {}", "✓".green(), installed);
+    Ok(())
+}
+
+/// Fetch and checksum the release tarball into `dir`, unpack it and swap the
+/// binary in for the running one. Returns what the new binary reports as its
+/// version.
+async fn download_and_replace(
+    http: &reqwest::Client,
+    url: &str,
+    artifact: &str,
+    dir: &Path,
+) -> Result<String> {
+    let sp = ui::spinner("Downloading...");
+    let tarball = format!("{}.tar.gz", artifact);
+    for (name, url) in [
+        (tarball.clone(), url.to_string()),
+        (format!("{}.sha256", tarball), format!("{}.sha256", url)),
+    ] {
+        let bytes = http
+            .get(&url)
+            .send()
+            .await?
+            .error_for_status()?
+            .bytes()
+            .await?;
+        std::fs::write(dir.join(name), bytes)?;
+    }
+    sp.finish_and_clear();
+
+    let run = |program: &str, args: &[&str]| -> Result<()> {
+        let output = Command::new(program).args(args).current_dir(dir).output()?;
+        if !output.status.success() {
+            return Err(QuomeError::ApiError(format!(
+                "{} failed: {}",
+                program,
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(())
+    };
+    run(
+        "shasum",
+        &["-a", "256", "-c", &format!("{}.sha256", tarball)],
+    )?;
+    run("tar", &["-xzf", &tarball])?;
+
+    // Rename within the install directory so the swap is atomic
+    let exe = std::env::current_exe()?;
+    let staged = exe.with_extension("new");
+    std::fs::copy(dir.join("quome"), &staged)?;
+    std::fs::rename(&staged, &exe)?;
+
+    let output = Command::new(&exe).arg("--version").output()?;
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// `1.2.3` from `1.2.3` or `v1.2.3`; anything but three numbers is refused.
+fn parse_version(raw: &str) -> Result<String> {
+    let version = raw.trim().trim_start_matches('v');
+    let parts: Vec<&str> = version.split('.').collect();
+    if parts.len() != 3 || parts.iter().any(|p| p.parse::<u64>().is_err()) {
+        return Err(QuomeError::ApiError(format!(
+            "Invalid version '{}': expected X.Y.Z, e.g. 0.2.1",
+            raw
+        )));
+    }
+    Ok(version.to_string())
+}
+
+/// Name of the release asset built for this OS and CPU, if there is one.
+fn release_artifact(os: &str, arch: &str) -> Option<&'static str> {
+    match (os, arch) {
+        ("macos", "aarch64") => Some("quome-darwin-arm64"),
+        ("macos", "x86_64") => Some("quome-darwin-x64"),
+        _ => None,
+    }
+}
+
+fn release_url(version: &str, artifact: &str) -> String {
+    format!("{}/v{}/{}.tar.gz", RELEASES_URL, version, artifact)
+}
+
+/// Newest version in the Homebrew tap, after refreshing it. `None` when the
+/// tap doesn't report one.
+pub fn latest_version() -> Result<Option<String>> {
+    // Check if brew is available
+    if Command::new("brew").arg("--version").output().is_err() {
+        return Err(QuomeError::ApiError(
+            "Homebrew not found. Please install quome manually or install Homebrew first.".into(),
+        ));
+    }
+
+    // Update brew to get latest formula info
+    let update = Command::new("brew").arg("update").output()?;
+    if !update.status.success() {
+        let stderr = String::from_utf8_lossy(&update.stderr);
+        return Err(QuomeError::ApiError(format!(
+            "brew update failed: {}",
+            stderr
+        )));
+    }
+
+    // Check what version is available
+    let info = Command::new("brew")
+        .args(["info", "quome-cloud/quome/quome", "--json=v2"])
+        .output()?;
+    if !info.status.success() {
+        return Ok(None);
+    }
+    let json: serde_json::Value = serde_json::from_slice(&info.stdout)?;
+    let latest = json["formulae"]
+        .get(0)
+        .and_then(|f| f["versions"]["stable"].as_str())
+        .map(|s| s.to_string());
+    if let Some(ref version) = latest {
+        cache::write(LATEST_ENTRY, version);
+    }
+    Ok(latest)
+}
+
+/// [`latest_version`], answered from the cache when it was checked in the
+/// last day.
+pub fn latest_version_cached() -> Result<Option<String>> {
+    match cache::read::<String>(LATEST_ENTRY, LATEST_MAX_AGE) {
+        Some(version) => Ok(Some(version)),
+        None => latest_version(),
+    }
+}
+
+/// Whether `latest` is a higher dotted version than `current`. Missing or
+/// non-numeric parts count as 0, so `0.3` > `0.2.9` and `1.0` == `1.0.0`.
+pub fn is_newer(latest: &str, current: &str) -> bool {
+    let parts = |v: &str| -> Vec<u64> {
+        v.trim_start_matches('v')
+            .split('.')
+            .map(|p| p.parse().unwrap_or(0))
+            .collect()
+    };
+    let (latest, current) = (parts(latest), parts(current));
+    let len = latest.len().max(current.len());
+    let at = |v: &[u64], i: usize| v.get(i).copied().unwrap_or(0);
+    (0..len)
+        .map(|i| at(&latest, i).cmp(&at(&current, i)))
+        .find(|o| o.is_ne())
+        .is_some_and(|o| o.is_gt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{is_newer, parse_version, release_artifact, release_url};
+
+    #[test]
+    fn compares_versions_numerically() {
+        assert!(is_newer("0.2.10", "0.2.9"));
+        assert!(is_newer("0.3", "0.2.9"));
+        assert!(is_newer("v1.0.0", "0.9.0"));
+        assert!(!is_newer("0.2.2", "0.2.2"));
+        assert!(!is_newer("1.0", "1.0.0"));
+        assert!(!is_newer("0.2.1", "0.2.2"));
+    }
+
+    #[test]
+    fn parses_pinned_versions() {
+        assert_eq!(parse_version("0.2.1").unwrap(), "0.2.1");
+        assert_eq!(parse_version("v1.10.0").unwrap(), "1.10.0");
+        assert!(parse_version("1.2").is_err());
+        assert!(parse_version("1.2.x").is_err());
+        assert!(parse_version("latest").is_err());
+    }
+
+    #[test]
+    fn release_assets_match_the_release_workflow() {
+        let artifact = release_artifact("macos", "aarch64").unwrap();
+        assert_eq!(
+            release_url("0.2.1", artifact),
+            "https://github.com/quome-cloud/quome-cli/releases/download/v0.2.1/quome-darwin-arm64.tar.gz"
+        );
+        assert_eq!(
+            release_artifact("macos", "x86_64"),
+            Some("quome-darwin-x64")
+        );
+        assert_eq!(release_artifact("linux", "x86_64"), None);
+    }
+}
@@ -1,12 +1,28 @@
 use clap::Parser;
+use colored::Colorize;
+use std::time::Duration;
 
-use crate::client::QuomeClient;
+use crate::api::models::User;
+use crate::cache;
+use crate::client::{self, QuomeClient};
 use crate::config::Config;
-use crate::errors::Result;
+use crate::errors::{QuomeError, Result};
+use crate::settings::Settings;
 use crate::ui;
 
+/// Cache entry holding the last user fetched by `whoami`.
+pub const CACHE_ENTRY: &str = "whoami.json";
+
 #[derive(Parser)]
 pub struct Args {
+    /// Answer from the local cache when fresh (for shell prompts)
+    #[arg(long)]
+    cached: bool,
+
+    /// Seconds a cached answer stays fresh
+    #[arg(long, default_value_t = 3600, requires = "cached")]
+    max_age: u64,
+
     /// Output as JSON
     #[arg(long)]
     json: bool,
@@ -14,22 +30,29 @@ pub struct Args {
 
 pub async fn execute(args: Args) -> Result<()> {
     let config = Config::load()?;
-    let token = config.require_token()?;
 
-    let client = QuomeClient::new(Some(&token), None)?;
-
-    let sp = ui::spinner("Fetching user info...");
-    let user = client.get_current_user().await?;
-    sp.finish_and_clear();
+    let user = if args.cached {
+        cached_user(&config, Duration::from_secs(args.max_age)).await?
+    } else {
+        let user = fetch_user(&config).await?;
+        if let Some(warning) = client::clock_skew().and_then(skew_warning) {
+            eprintln!("{} {}", "warning:".yellow().bold(), warning);
+        }
+        user
+    };
 
-    if args.json {
-        println!("{}", serde_json::to_string_pretty(&user)?);
+    if ui::structured(args.json) {
+        ui::print_structured(&user)?;
     } else {
         let mut details = vec![
             ("ID", user.id.to_string()),
             ("Name", user.name.clone()),
             ("Email", user.email.clone()),
         ];
+        if let Some(enabled) = user.two_factor {
+            let label = if enabled { "enabled" } else { "disabled" };
+            details.push(("Two-factor", label.to_string()));
+        }
 
         // Add linked context if any
         if let Some(linked) = config.get_linked()? {
@@ -43,7 +66,89 @@ pub async fn execute(args: Args) -> Result<()> {
             details.iter().map(|(k, v)| (*k, v.as_str())).collect();
 
         ui::print_detail(&user.name, &details_ref);
+
+        if user.two_factor == Some(false) {
+            let website = Settings::load().unwrap_or_default().website_url;
+            println!(
+                "Turn on two-factor authentication at {}/settings",
+                website.trim_end_matches('/')
+            );
+        }
     }
 
     Ok(())
 }
+
+/// Describe a clock skew worth mentioning; `skew` is server minus local time.
+fn skew_warning(skew: chrono::Duration) -> Option<String> {
+    if skew.abs() <= client::SKEW_TOLERANCE {
+        return None;
+    }
+    let by = cache::format_age(skew.abs().to_std().unwrap_or_default());
+    let direction = if skew > chrono::Duration::zero() {
+        "behind"
+    } else {
+        "ahead of"
+    };
+    Some(format!(
+        "your clock is {} {} the Quome API's; expiry checks use the API's clock",
+        by, direction
+    ))
+}
+
+async fn fetch_user(config: &Config) -> Result<User> {
+    let token = config.require_token()?;
+    let client = QuomeClient::new(Some(&token), None)?;
+
+    let sp = ui::spinner("Fetching user info...");
+    let user = client.get_current_user().await;
+    sp.finish_and_clear();
+
+    let user = user?;
+    cache::write(CACHE_ENTRY, &user);
+    Ok(user)
+}
+
+/// Fresh cache → no network. Otherwise refresh, and if the API can't be
+/// reached fall back to whatever was cached last, however old.
+async fn cached_user(config: &Config, max_age: Duration) -> Result<User> {
+    // The entry is only known to match the saved login; a QUOME_TOKEN key
+    // could belong to anyone
+    let token = config.require_token()?;
+    let Some(saved) = config.user.as_ref().filter(|u| u.token == token) else {
+        return Err(QuomeError::ApiError(
+            "--cached only works for the saved login, not a QUOME_TOKEN key; run `quome whoami` without it".into(),
+        ));
+    };
+    let belongs = |u: &User| u.id == saved.id;
+
+    if let Some(user) = cache::read::<User>(CACHE_ENTRY, max_age).filter(belongs) {
+        return Ok(user);
+    }
+
+    match fetch_user(config).await {
+        Err(e @ (QuomeError::Http(_) | QuomeError::RateLimited)) => {
+            cache::read::<User>(CACHE_ENTRY, Duration::MAX)
+                .filter(belongs)
+                .ok_or(e)
+        }
+        result => result,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn warns_only_past_the_tolerance() {
+        assert_eq!(skew_warning(chrono::Duration::seconds(-12)), None);
+        assert_eq!(
+            skew_warning(chrono::Duration::seconds(312)).unwrap(),
+            "your clock is 5m behind the Quome API's; expiry checks use the API's clock"
+        );
+        assert!(skew_warning(chrono::Duration::hours(-2))
+            .unwrap()
+            .contains("2h ahead of"));
+    }
+}
@@ -1,20 +1,44 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
 use uuid::Uuid;
 
 use crate::errors::{QuomeError, Result};
+use crate::util;
 
 const CONFIG_DIR: &str = ".quome";
 const CONFIG_FILE: &str = "config.json";
 
-#[derive(Debug, Serialize, Deserialize, Default)]
+/// Bumped whenever the shape of `config.json` changes; [`migrate`] upgrades
+/// older files on load.
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// A temp file this old is left over from a killed write, not one in progress.
+const STALE_TMP_AGE: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Config {
+    /// Files written before versioning have none, which reads as 0
+    #[serde(default)]
+    pub schema_version: u32,
     #[serde(default)]
     pub user: Option<UserConfig>,
     #[serde(default)]
     pub linked: HashMap<String, LinkedContext>,
+    /// Organization used where no directory is linked, set by `orgs switch`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_org: Option<DefaultOrg>,
+    /// User-defined command aliases, expanded before argument parsing
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub aliases: HashMap<String, String>,
+}
+
+/// An upgrade done by [`Config::load_migrated`].
+pub struct Migration {
+    pub from: u32,
+    pub backup: PathBuf,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
@@ -24,6 +48,12 @@ pub struct UserConfig {
     pub email: String,
 }
 
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct DefaultOrg {
+    pub id: Uuid,
+    pub name: String,
+}
+
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct LinkedContext {
     pub org_id: Uuid,
@@ -34,8 +64,20 @@ pub struct LinkedContext {
     pub app_name: Option<String>,
 }
 
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            schema_version: SCHEMA_VERSION,
+            user: None,
+            linked: HashMap::new(),
+            default_org: None,
+            aliases: HashMap::new(),
+        }
+    }
+}
+
 impl Config {
-    fn config_dir() -> Result<PathBuf> {
+    pub fn config_dir() -> Result<PathBuf> {
         let home = dirs::home_dir().ok_or_else(|| {
             QuomeError::Io(std::io::Error::new(
                 std::io::ErrorKind::NotFound,
@@ -50,23 +92,80 @@ impl Config {
     }
 
     pub fn load() -> Result<Self> {
+        let (config, migrated) = Self::load_migrated()?;
+        if let Some(ref migration) = migrated {
+            eprintln!(
+                "Upgraded {} to schema version {} (backup at {})",
+                CONFIG_FILE,
+                SCHEMA_VERSION,
+                migration.backup.display()
+            );
+        }
+        Ok(config)
+    }
+
+    /// Just the aliases, read without migrating the file so that happens
+    /// (and is reported) once the command itself loads the config.
+    pub fn read_aliases() -> HashMap<String, String> {
+        Self::config_path()
+            .ok()
+            .and_then(|path| fs::read_to_string(path).ok())
+            .and_then(|content| serde_json::from_str::<serde_json::Value>(&content).ok())
+            .and_then(|mut value| serde_json::from_value(value["aliases"].take()).ok())
+            .unwrap_or_default()
+    }
+
+    /// Load the config, upgrading an older file in place after copying the
+    /// original, minus the API token, next to it. Reports what was done, if
+    /// anything.
+    pub fn load_migrated() -> Result<(Self, Option<Migration>)> {
         let path = Self::config_path()?;
+        if let Some(dir) = path.parent() {
+            remove_stale_tmp_files(dir);
+        }
 
         if !path.exists() {
-            return Ok(Self::default());
+            return Ok((Self::default(), None));
         }
 
         let content = fs::read_to_string(&path)?;
-        let config: Config = serde_json::from_str(&content)?;
-        Ok(config)
+        let mut value: serde_json::Value = serde_json::from_str(&content)?;
+        let from = migrate(&mut value)?;
+        let config: Config = serde_json::from_value(value)?;
+        if from == SCHEMA_VERSION {
+            return Ok((config, None));
+        }
+
+        let backup = path.with_file_name(format!("{}.v{}.bak", CONFIG_FILE, from));
+        util::write_private(&backup, without_token(&content)?.as_bytes(), true)?;
+        config.save()?;
+        Ok((config, Some(Migration { from, backup })))
+    }
+
+    /// Delete the `config.json.v<N>.bak` files left by migrations.
+    pub fn remove_backups() -> Result<()> {
+        let dir = Self::config_dir()?;
+        let Ok(entries) = fs::read_dir(&dir) else {
+            return Ok(());
+        };
+        for entry in entries.flatten() {
+            if entry.file_name().to_str().is_some_and(is_backup_file) {
+                fs::remove_file(entry.path())?;
+            }
+        }
+        Ok(())
     }
 
     pub fn save(&self) -> Result<()> {
         let dir = Self::config_dir()?;
         fs::create_dir_all(&dir)?;
 
+        remove_stale_tmp_files(&dir);
+
+        // Per-process temp name so concurrent invocations don't clobber each
+        // other's half-written file; rename is the atomic step.
         let path = Self::config_path()?;
-        let tmp_path = path.with_extension("tmp");
+        let tmp_path = dir.join(format!("{}.{}.tmp", CONFIG_FILE, std::process::id()));
 
         let content = serde_json::to_string_pretty(self)?;
         fs::write(&tmp_path, content)?;
@@ -130,7 +229,10 @@ impl Config {
                 .map_err(|_| QuomeError::ApiError("Invalid QUOME_ORG UUID".into()));
         }
 
-        Ok(self.get_linked()?.map(|l| l.org_id))
+        Ok(self
+            .get_linked()?
+            .map(|l| l.org_id)
+            .or_else(|| self.default_org.as_ref().map(|d| d.id)))
     }
 
     pub fn require_linked_org(&self) -> Result<Uuid> {
@@ -164,4 +266,182 @@ impl Config {
         self.linked.remove(&key);
         Ok(())
     }
+
+    /// Linked directories that no longer exist on disk, sorted.
+    pub fn stale_links(&self) -> Vec<String> {
+        let mut stale: Vec<String> = self
+            .linked
+            .keys()
+            .filter(|dir| !Path::new(dir).is_dir())
+            .cloned()
+            .collect();
+        stale.sort();
+        stale
+    }
+}
+
+/// Upgrade a parsed `config.json` to [`SCHEMA_VERSION`] one version at a
+/// time, returning the version it started at. A file from a newer CLI is
+/// refused rather than loaded, since saving it would drop fields we don't know.
+fn migrate(value: &mut serde_json::Value) -> Result<u32> {
+    let Some(map) = value.as_object_mut() else {
+        // Not a config at all; let deserialization report it
+        return Ok(SCHEMA_VERSION);
+    };
+    let from = map
+        .get("schema_version")
+        .and_then(|v| v.as_u64())
+        .unwrap_or(0) as u32;
+    if from > SCHEMA_VERSION {
+        return Err(QuomeError::ConfigTooNew(from));
+    }
+
+    for version in from..SCHEMA_VERSION {
+        match version {
+            // Everything written before versioning: same fields, no version
+            0 => {}
+            _ => unreachable!("no migration from schema version {}", version),
+        }
+    }
+    map.insert("schema_version".into(), SCHEMA_VERSION.into());
+    Ok(from)
+}
+
+/// `content` of a config file with `user.token` removed, for backups that
+/// shouldn't outlive `logout` with a credential in them.
+fn without_token(content: &str) -> Result<String> {
+    let mut value: serde_json::Value = serde_json::from_str(content)?;
+    if let Some(user) = value.get_mut("user").and_then(|u| u.as_object_mut()) {
+        user.remove("token");
+    }
+    Ok(serde_json::to_string_pretty(&value)?)
+}
+
+/// Backups written by [`Config::load_migrated`]: `config.json.v<N>.bak`.
+fn is_backup_file(name: &str) -> bool {
+    name.strip_prefix(CONFIG_FILE)
+        .and_then(|rest| rest.strip_prefix(".v"))
+        .and_then(|rest| rest.strip_suffix(".bak"))
+        .is_some_and(|v| !v.is_empty() && v.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// Temp files written by [`Config::save`], including the pre-pid `config.tmp`.
+fn is_tmp_file(name: &str) -> bool {
+    name == "config.tmp"
+        || name
+            .strip_prefix(CONFIG_FILE)
+            .and_then(|rest| rest.strip_prefix('.'))
+            .and_then(|rest| rest.strip_suffix(".tmp"))
+            .is_some_and(|pid| !pid.is_empty() && pid.bytes().all(|b| b.is_ascii_digit()))
+}
+
+/// Best-effort removal of temp files orphaned by a write that was killed
+/// between `write` and `rename`.
+fn remove_stale_tmp_files(dir: &Path) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let stale = entry
+            .metadata()
+            .and_then(|m| m.modified())
+            .ok()
+            .and_then(|t| t.elapsed().ok())
+            .is_some_and(|age| age > STALE_TMP_AGE);
+        if stale && entry.file_name().to_str().is_some_and(is_tmp_file) {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        is_backup_file, is_tmp_file, migrate, without_token, Config, LinkedContext, SCHEMA_VERSION,
+    };
+    use crate::errors::QuomeError;
+    use uuid::Uuid;
+
+    #[test]
+    fn recognizes_only_config_temp_files() {
+        assert!(is_tmp_file("config.json.4242.tmp"));
+        assert!(is_tmp_file("config.tmp"));
+        assert!(!is_tmp_file("config.json"));
+        assert!(!is_tmp_file("config.json..tmp"));
+        assert!(!is_tmp_file("config.json.abc.tmp"));
+        assert!(!is_tmp_file("settings.json.12.tmp"));
+    }
+
+    #[test]
+    fn stale_links_are_missing_directories() {
+        let link = || LinkedContext {
+            org_id: Uuid::nil(),
+            org_name: "acme".into(),
+            app_id: None,
+            app_name: None,
+        };
+        let here = std::env::temp_dir().to_string_lossy().to_string();
+        let gone = "/nonexistent/quome-test/project".to_string();
+
+        let mut config = Config::default();
+        config.linked.insert(here, link());
+        config.linked.insert(gone.clone(), link());
+        assert_eq!(config.stale_links(), vec![gone]);
+    }
+
+    #[test]
+    fn migrates_unversioned_config() {
+        let mut value = serde_json::json!({
+            "user": {
+                "token": "qk_live_abc",
+                "id": "a1b2c3d4-0000-4000-8000-000000000001",
+                "email": "you@example.com"
+            },
+            "linked": {
+                "/home/ada/api": {
+                    "org_id": "0d9f4a3b-0000-4000-8000-000000000001",
+                    "org_name": "acme"
+                }
+            },
+            "aliases": { "d": "deployments create" }
+        });
+
+        assert_eq!(migrate(&mut value).unwrap(), 0);
+        let config: Config = serde_json::from_value(value.clone()).unwrap();
+        assert_eq!(config.schema_version, SCHEMA_VERSION);
+        assert_eq!(config.user.unwrap().email, "you@example.com");
+        assert_eq!(config.linked["/home/ada/api"].org_name, "acme");
+        assert_eq!(config.aliases["d"], "deployments create");
+
+        // Already current: nothing to do
+        assert_eq!(migrate(&mut value).unwrap(), SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn backups_leave_out_the_token() {
+        let content = r#"{"user":{"token":"qk_live_abc","id":"a1b2","email":"you@example.com"},"aliases":{}}"#;
+        let backup = without_token(content).unwrap();
+        assert!(!backup.contains("qk_live_abc"), "{}", backup);
+        assert!(backup.contains("you@example.com"), "{}", backup);
+        assert!(without_token(r#"{"linked":{}}"#).is_ok());
+    }
+
+    #[test]
+    fn recognizes_only_config_backups() {
+        assert!(is_backup_file("config.json.v0.bak"));
+        assert!(is_backup_file("config.json.v12.bak"));
+        assert!(!is_backup_file("config.json"));
+        assert!(!is_backup_file("config.json.v.bak"));
+        assert!(!is_backup_file("config.json.vx.bak"));
+        assert!(!is_backup_file("settings.json.v0.bak"));
+    }
+
+    #[test]
+    fn refuses_config_from_newer_cli() {
+        let mut value = serde_json::json!({ "schema_version": SCHEMA_VERSION + 1 });
+        assert!(matches!(
+            migrate(&mut value),
+            Err(QuomeError::ConfigTooNew(v)) if v == SCHEMA_VERSION + 1
+        ));
+    }
 }
@@ -0,0 +1,216 @@
+use clap::Args;
+use serde::de::IgnoredAny;
+use uuid::Uuid;
+
+use crate::api::models::{App, Organization};
+use crate::cache;
+use crate::client::QuomeClient;
+use crate::config::Config;
+use crate::errors::{QuomeError, Result};
+
+/// `--org` / `--org-name`, flattened into every org-scoped command.
+#[derive(Args)]
+pub struct OrgArgs {
+    /// Organization ID (uses linked org if not provided)
+    #[arg(long)]
+    pub org: Option<Uuid>,
+
+    /// Organization name or slug, resolved via the org list
+    #[arg(long, conflicts_with = "org")]
+    pub org_name: Option<String>,
+}
+
+impl OrgArgs {
+    pub async fn resolve(&self, config: &Config, client: &QuomeClient) -> Result<Uuid> {
+        resolve_org(config, client, self.org, self.org_name.as_deref()).await
+    }
+
+    /// [`resolve`](Self::resolve), but under `--offline` a name is looked up
+    /// in the org list `orgs list` last cached.
+    pub async fn resolve_cached(
+        &self,
+        config: &Config,
+        client: &QuomeClient,
+        offline: bool,
+    ) -> Result<Uuid> {
+        if offline {
+            resolve_org_offline(config, self.org, self.org_name.as_deref())
+        } else {
+            self.resolve(config, client).await
+        }
+    }
+}
+
+/// `--app` / `--app-name`, flattened into every app-scoped command.
+#[derive(Args)]
+pub struct AppArgs {
+    /// Application ID (uses linked app if not provided)
+    #[arg(long)]
+    pub app: Option<Uuid>,
+
+    /// Application name or slug, resolved via the app list
+    #[arg(long, conflicts_with = "app")]
+    pub app_name: Option<String>,
+}
+
+impl AppArgs {
+    pub async fn resolve(
+        &self,
+        config: &Config,
+        client: &QuomeClient,
+        org_id: Uuid,
+    ) -> Result<Uuid> {
+        resolve_app(config, client, org_id, self.app, self.app_name.as_deref()).await
+    }
+
+    /// [`resolve`](Self::resolve), but under `--offline` a name is looked up
+    /// in the app list `apps list` last cached for `org_id`.
+    pub async fn resolve_cached(
+        &self,
+        config: &Config,
+        client: &QuomeClient,
+        org_id: Uuid,
+        offline: bool,
+    ) -> Result<Uuid> {
+        if offline {
+            resolve_app_offline(config, org_id, self.app, self.app_name.as_deref())
+        } else {
+            self.resolve(config, client, org_id).await
+        }
+    }
+}
+
+/// Pick the org from an explicit ID, then a name lookup, then `QUOME_ORG` or
+/// the linked directory.
+pub async fn resolve_org(
+    config: &Config,
+    client: &QuomeClient,
+    id: Option<Uuid>,
+    name: Option<&str>,
+) -> Result<Uuid> {
+    if let Some(id) = id {
+        return Ok(id);
+    }
+    let Some(name) = name else {
+        return config.require_linked_org();
+    };
+
+    let orgs = client.list_orgs().await?;
+    pick_org(orgs.iter(), name)
+}
+
+/// [`resolve_org`] without the network: a name is matched against the cached
+/// `orgs list` result.
+pub fn resolve_org_offline(config: &Config, id: Option<Uuid>, name: Option<&str>) -> Result<Uuid> {
+    if let Some(id) = id {
+        return Ok(id);
+    }
+    let Some(name) = name else {
+        return config.require_linked_org();
+    };
+
+    // `orgs list` caches each org with your role, which isn't needed here
+    let orgs: Vec<(Organization, IgnoredAny)> =
+        cache::offline("orgs").ok_or_else(|| not_cached("orgs list"))?;
+    pick_org(orgs.iter().map(|(org, _)| org), name)
+}
+
+/// Same as [`resolve_org`] for applications within `org_id`.
+pub async fn resolve_app(
+    config: &Config,
+    client: &QuomeClient,
+    org_id: Uuid,
+    id: Option<Uuid>,
+    name: Option<&str>,
+) -> Result<Uuid> {
+    if let Some(id) = id {
+        return Ok(id);
+    }
+    let Some(name) = name else {
+        return config.require_linked_app();
+    };
+
+    let apps = client.list_apps(org_id).await?.data;
+    pick_app(&apps, name)
+}
+
+/// [`resolve_app`] without the network: a name is matched against the
+/// cached `apps list` result for `org_id`.
+pub fn resolve_app_offline(
+    config: &Config,
+    org_id: Uuid,
+    id: Option<Uuid>,
+    name: Option<&str>,
+) -> Result<Uuid> {
+    if let Some(id) = id {
+        return Ok(id);
+    }
+    let Some(name) = name else {
+        return config.require_linked_app();
+    };
+
+    let apps: Vec<App> =
+        cache::offline(&format!("apps-{}", org_id)).ok_or_else(|| not_cached("apps list"))?;
+    pick_app(&apps, name)
+}
+
+fn not_cached(command: &str) -> QuomeError {
+    QuomeError::NotFound(format!(
+        "no cached list to look the name up in; run `quome {}` once without --offline, or pass the ID",
+        command
+    ))
+}
+
+fn pick_org<'a>(orgs: impl Iterator<Item = &'a Organization>, name: &str) -> Result<Uuid> {
+    let matches = orgs
+        .filter(|o| o.name.eq_ignore_ascii_case(name) || o.slug == name)
+        .map(|o| o.id);
+    pick(matches, "organization", name)
+}
+
+fn pick_app(apps: &[App], name: &str) -> Result<Uuid> {
+    let matches = apps
+        .iter()
+        .filter(|a| a.name.eq_ignore_ascii_case(name) || a.slug.as_deref() == Some(name))
+        .map(|a| a.id);
+    pick(matches, "application", name)
+}
+
+fn pick(matches: impl Iterator<Item = Uuid>, kind: &str, name: &str) -> Result<Uuid> {
+    let ids: Vec<Uuid> = matches.collect();
+    match ids.as_slice() {
+        [id] => Ok(*id),
+        [] => Err(QuomeError::NotFound(format!(
+            "no {} named '{}'",
+            kind, name
+        ))),
+        _ => Err(QuomeError::AmbiguousName(format!(
+            "'{}' matches {} {}s",
+            name,
+            ids.len(),
+            kind
+        ))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::pick;
+    use crate::errors::QuomeError;
+    use uuid::Uuid;
+
+    #[test]
+    fn pick_requires_exactly_one_match() {
+        let a = Uuid::nil();
+        let b = Uuid::max();
+        assert_eq!(pick([a].into_iter(), "app", "web").unwrap(), a);
+        assert!(matches!(
+            pick([].into_iter(), "app", "web"),
+            Err(QuomeError::NotFound(_))
+        ));
+        assert!(matches!(
+            pick([a, b].into_iter(), "app", "web"),
+            Err(QuomeError::AmbiguousName(_))
+        ));
+    }
+}
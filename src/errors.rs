@@ -2,7 +2,7 @@ use thiserror::Error;
 
 #[derive(Error, Debug)]
 pub enum QuomeError {
-    #[error("Not logged in. Run `quome login` first.")]
+    #[error("Not logged in. Run `quome init` (or `quome login`) first.")]
     NotLoggedIn,
 
     #[error("No linked organization. Run `quome link` to connect.")]
@@ -14,12 +14,52 @@ pub enum QuomeError {
     #[error("Unauthorized. Your session may have expired. Run `quome login`.")]
     Unauthorized,
 
+    #[error("Already logged in as {0}. Pass --force to replace the session.")]
+    AlreadyLoggedIn(String),
+
+    #[error("Ambiguous name: {0}. Pass the ID instead.")]
+    AmbiguousName(String),
+
+    #[error("Alias error: {0}")]
+    Alias(String),
+
+    #[error("Invalid header: {0}. Expected \"Name: value\".")]
+    InvalidHeader(String),
+
+    #[error("Invalid timestamp format '{0}'. Use strftime codes like %Y-%m-%d %H:%M.")]
+    InvalidTimeFormat(String),
+
+    #[error(
+        "Invalid date '{0}'. Use YYYY-MM-DD, an RFC 3339 timestamp, or a duration ago like 15m, 2h or 1d."
+    )]
+    InvalidDate(String),
+
+    #[error(
+        "Couldn't detect your shell from $SHELL ('{0}'). Name it, e.g. `quome completions zsh`."
+    )]
+    UnknownShell(String),
+
+    #[error("Invalid manifest {0}")]
+    InvalidManifest(String),
+
+    #[error(
+        "~/.quome/config.json uses schema version {0}, newer than this quome understands. Run `quome upgrade`."
+    )]
+    ConfigTooNew(u32),
+
     #[error("Not found: {0}")]
     NotFound(String),
 
     #[error("API error: {0}")]
     ApiError(String),
 
+    /// A 403. Reads like any other API error, but callers can tell it apart.
+    #[error("API error: {0}")]
+    Forbidden(String),
+
+    #[error("Timed out after {0}s waiting for the operation to finish")]
+    Timeout(u64),
+
     #[error("Rate limited. Please wait and try again.")]
     RateLimited,
 
@@ -34,6 +74,9 @@ pub enum QuomeError {
 
     #[error(transparent)]
     Json(#[from] serde_json::Error),
+
+    #[error(transparent)]
+    Yaml(#[from] serde_yaml::Error),
 }
 
 pub type Result<T> = std::result::Result<T, QuomeError>;
@@ -1,12 +1,18 @@
+mod alias;
 mod api;
+mod bulk;
+mod cache;
 mod client;
 mod commands;
 mod config;
+mod context;
 mod errors;
+mod registry;
 mod settings;
 mod ui;
+mod util;
 
-use clap::Parser;
+use clap::{CommandFactory, Parser};
 use colored::Colorize;
 
 const BANNER: &str = r#"
@@ -23,19 +29,54 @@ const BANNER: &str = r#"
 #[command(about = "CLI for the Quome platform")]
 #[command(version)]
 #[command(before_help = BANNER)]
+#[command(next_help_heading = "Global Options")]
 struct Cli {
+    /// Extra request header, e.g. "X-Debug: 1" (repeatable)
+    #[arg(long = "header", global = true, value_name = "NAME: VALUE")]
+    headers: Vec<String>,
+
+    /// Show timestamps in your local timezone instead of UTC
+    #[arg(long, global = true)]
+    local: bool,
+
+    /// strftime pattern for timestamps, e.g. "%d %b %H:%M"
+    #[arg(long, global = true, value_name = "FORMAT")]
+    timestamp_format: Option<String>,
+
+    /// Print each API request's method, path, status and latency to stderr at the end
+    #[arg(long, global = true)]
+    trace_timing: bool,
+
+    /// Plain output without colors or styling (also: NO_COLOR=1)
+    #[arg(long, global = true)]
+    no_color: bool,
+
+    /// Output format; a command's --json is the same as -o json
+    #[arg(
+        short,
+        long,
+        global = true,
+        value_enum,
+        default_value_t = ui::OutputFormat::Table
+    )]
+    output: ui::OutputFormat,
+
     #[command(subcommand)]
     command: Commands,
 }
 
 #[derive(clap::Subcommand)]
 enum Commands {
+    /// Set up the CLI: pick an API, log in, link this directory
+    Init(commands::init::Args),
     /// Login to Quome
     Login(commands::login::Args),
     /// Logout from Quome
     Logout(commands::logout::Args),
     /// Show current user info
     Whoami(commands::whoami::Args),
+    /// Show CLI version, login and linked context
+    Status(commands::status::Args),
     /// Link current directory to an org and app
     Link(commands::link::Args),
     /// Unlink current directory
@@ -80,18 +121,61 @@ enum Commands {
     },
     /// View organization audit events
     Events(commands::events::Args),
-    /// Upgrade quome to the latest version
-    Upgrade,
+    /// List any resource: apps, dbs, secrets, deployments, members, keys, events
+    Ls(commands::ls::Args),
+    /// Inspect or clear locally cached data
+    Cache {
+        #[command(subcommand)]
+        command: commands::cache::CacheCommands,
+    },
+    /// Manage the local config file
+    Config {
+        #[command(subcommand)]
+        command: commands::config::ConfigCommands,
+    },
+    /// Generate or install shell completions
+    #[command(hide = true)]
+    Completions(commands::completions::Args),
+    /// Upgrade quome to the latest version (or a specific one)
+    Upgrade(commands::upgrade::Args),
 }
 
 #[tokio::main]
 async fn main() {
-    let cli = Cli::parse();
+    let started = std::time::Instant::now();
+    ui::set_color(false);
+    let argv = match expand_aliases() {
+        Ok(argv) => argv,
+        Err(e) => {
+            eprintln!("{} {}", "error:".red().bold(), e);
+            std::process::exit(1);
+        }
+    };
+    let cli = match Cli::parse_from(&argv) {
+        Cli {
+            command: Commands::Ls(args),
+            ..
+        } => match commands::ls::expand(&argv, args, &valued_global_options()) {
+            Ok(expanded) => Cli::parse_from(expanded),
+            Err(e) => {
+                eprintln!("{} {}", "error:".red().bold(), e);
+                std::process::exit(1);
+            }
+        },
+        cli => cli,
+    };
+
+    if let Err(e) = apply_global_flags(&cli) {
+        eprintln!("{} {}", "error:".red().bold(), e);
+        std::process::exit(1);
+    }
 
     let result = match cli.command {
+        Commands::Init(args) => commands::init::execute(args).await,
         Commands::Login(args) => commands::login::execute(args).await,
         Commands::Logout(args) => commands::logout::execute(args).await,
         Commands::Whoami(args) => commands::whoami::execute(args).await,
+        Commands::Status(args) => commands::status::execute(args).await,
         Commands::Link(args) => commands::link::execute(args).await,
         Commands::Unlink(args) => commands::unlink::execute(args).await,
         Commands::Orgs { command } => commands::orgs::execute(command).await,
@@ -103,11 +187,73 @@ async fn main() {
         Commands::Secrets { command } => commands::secrets::execute(command).await,
         Commands::Keys { command } => commands::keys::execute(command).await,
         Commands::Events(args) => commands::events::execute(args).await,
-        Commands::Upgrade => commands::upgrade::execute().await,
+        Commands::Ls(_) => Err(errors::QuomeError::ApiError(
+            "`quome ls` didn't expand to a list command".into(),
+        )),
+        Commands::Cache { command } => commands::cache::execute(command).await,
+        Commands::Config { command } => commands::config::execute(command).await,
+        Commands::Completions(args) => commands::completions::execute(args, Cli::command()),
+        Commands::Upgrade(args) => commands::upgrade::execute(args).await,
     };
 
+    client::report_timings(started.elapsed());
     if let Err(e) = result {
-        eprintln!("{} {}", "error:".red().bold(), e);
+        eprintln!("{} {}", "error:".red().bold(), util::redact(&e.to_string()));
         std::process::exit(1);
     }
 }
+
+/// Settings that affect every command: extra headers, request timing,
+/// color, output format, hyperlinks and timestamp display. Flags win over `settings.json`.
+fn apply_global_flags(cli: &Cli) -> errors::Result<()> {
+    client::set_extra_headers(&cli.headers)?;
+    if cli.trace_timing {
+        client::enable_trace_timing();
+    }
+    ui::set_color(cli.no_color);
+    ui::set_output(cli.output);
+
+    let settings = settings::Settings::load().unwrap_or_default();
+    ui::set_hyperlinks(if cli.no_color {
+        Some(false)
+    } else {
+        settings.hyperlinks
+    });
+    ui::set_time_display(
+        cli.local || settings.local_time,
+        cli.timestamp_format.clone().or(settings.timestamp_format),
+    )
+}
+
+/// argv with any alias from `~/.quome/config.json` expanded. A config that
+/// can't be read just means no aliases; the command itself will report it.
+fn expand_aliases() -> errors::Result<Vec<std::ffi::OsString>> {
+    let args: Vec<_> = std::env::args_os().collect();
+    let aliases = config::Config::read_aliases();
+    if aliases.is_empty() {
+        return Ok(args);
+    }
+
+    let builtins: Vec<String> = Cli::command()
+        .get_subcommands()
+        .flat_map(|c| std::iter::once(c.get_name()).chain(c.get_all_aliases()))
+        .map(String::from)
+        .chain(["help".to_string()])
+        .collect();
+    alias::expand(args, &aliases, &builtins, &valued_global_options())
+}
+
+/// Top-level options that take their value as the next argument, in every
+/// spelling (`--header`, `-o`, `--output`), so a scan for the command word
+/// can step over them.
+fn valued_global_options() -> Vec<String> {
+    Cli::command()
+        .get_arguments()
+        .filter(|a| a.get_action().takes_values() && !a.is_positional())
+        .flat_map(|a| {
+            let long = a.get_long().map(|l| format!("--{}", l));
+            let short = a.get_short().map(|s| format!("-{}", s));
+            long.into_iter().chain(short)
+        })
+        .collect()
+}
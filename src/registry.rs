@@ -0,0 +1,206 @@
+use reqwest::header::{HeaderMap, HeaderValue, ACCEPT, AUTHORIZATION, WWW_AUTHENTICATE};
+use reqwest::StatusCode;
+use std::time::Duration;
+
+use crate::errors::{QuomeError, Result};
+
+const DOCKER_HUB: &str = "registry-1.docker.io";
+
+const MANIFEST_TYPES: &str = "application/vnd.oci.image.index.v1+json, \
+    application/vnd.docker.distribution.manifest.list.v2+json, \
+    application/vnd.oci.image.manifest.v1+json, \
+    application/vnd.docker.distribution.manifest.v2+json";
+
+/// An image reference split into the parts the registry API needs.
+#[derive(Debug, PartialEq)]
+pub struct ImageRef {
+    pub registry: String,
+    pub repository: String,
+    /// Tag or `sha256:` digest
+    pub reference: String,
+}
+
+/// Parse `nginx:1.27`, `acme/api`, `ghcr.io/acme/api:v1` or `repo@sha256:...`,
+/// applying Docker Hub defaults the same way `docker pull` does.
+pub fn parse_image_ref(image: &str) -> ImageRef {
+    let (name, reference) = match image.split_once('@') {
+        Some((name, digest)) => (name, digest.to_string()),
+        None => match image.rsplit_once(':') {
+            // A colon before the last slash is a registry port, not a tag
+            Some((name, tag)) if !tag.contains('/') => (name, tag.to_string()),
+            _ => (image, "latest".to_string()),
+        },
+    };
+
+    let (registry, repository) = match name.split_once('/') {
+        Some((host, rest)) if host.contains('.') || host.contains(':') || host == "localhost" => {
+            (host.to_string(), rest.to_string())
+        }
+        _ => (DOCKER_HUB.to_string(), name.to_string()),
+    };
+
+    let repository = if registry == DOCKER_HUB && !repository.contains('/') {
+        format!("library/{}", repository)
+    } else {
+        repository
+    };
+
+    ImageRef {
+        registry,
+        repository,
+        reference,
+    }
+}
+
+/// Ports the image declares with `EXPOSE`, read anonymously from its registry.
+pub async fn exposed_ports(image: &str) -> Result<Vec<u16>> {
+    let image = parse_image_ref(image);
+    let http = reqwest::Client::builder()
+        .timeout(Duration::from_secs(10))
+        .build()?;
+    let base = format!("https://{}/v2/{}", image.registry, image.repository);
+
+    let mut token = None;
+    let mut manifest = fetch_json(
+        &http,
+        &format!("{}/manifests/{}", base, image.reference),
+        &mut token,
+    )
+    .await?;
+
+    // Multi-arch index: pick linux/amd64 (what the platform runs), else the first entry
+    if let Some(manifests) = manifest.get("manifests").and_then(|m| m.as_array()) {
+        let chosen = manifests
+            .iter()
+            .find(|m| m["platform"]["os"] == "linux" && m["platform"]["architecture"] == "amd64")
+            .or_else(|| manifests.first())
+            .and_then(|m| m["digest"].as_str())
+            .ok_or(QuomeError::InvalidResponse)?;
+        manifest = fetch_json(&http, &format!("{}/manifests/{}", base, chosen), &mut token).await?;
+    }
+
+    let config_digest = manifest["config"]["digest"]
+        .as_str()
+        .ok_or(QuomeError::InvalidResponse)?;
+    let config = fetch_json(
+        &http,
+        &format!("{}/blobs/{}", base, config_digest),
+        &mut token,
+    )
+    .await?;
+
+    let mut ports: Vec<u16> = config["config"]["ExposedPorts"]
+        .as_object()
+        .map(|ports| {
+            ports
+                .keys()
+                .filter_map(|p| p.split('/').next()?.parse().ok())
+                .collect()
+        })
+        .unwrap_or_default();
+    ports.sort_unstable();
+    ports.dedup();
+    Ok(ports)
+}
+
+/// GET a registry JSON document, performing the anonymous bearer-token dance
+/// on the first 401 and reusing the token afterwards.
+async fn fetch_json(
+    http: &reqwest::Client,
+    url: &str,
+    token: &mut Option<String>,
+) -> Result<serde_json::Value> {
+    for _ in 0..2 {
+        let mut headers = HeaderMap::new();
+        headers.insert(ACCEPT, HeaderValue::from_static(MANIFEST_TYPES));
+        if let Some(t) = token.as_deref() {
+            let value = HeaderValue::from_str(&format!("Bearer {}", t))
+                .map_err(|_| QuomeError::InvalidResponse)?;
+            headers.insert(AUTHORIZATION, value);
+        }
+
+        let response = http.get(url).headers(headers).send().await?;
+        if response.status() == StatusCode::UNAUTHORIZED && token.is_none() {
+            let challenge = response
+                .headers()
+                .get(WWW_AUTHENTICATE)
+                .and_then(|v| v.to_str().ok())
+                .ok_or(QuomeError::Unauthorized)?;
+            *token = Some(anonymous_token(http, challenge).await?);
+            continue;
+        }
+        if !response.status().is_success() {
+            return Err(QuomeError::ApiError(format!(
+                "registry returned {} for {}",
+                response.status(),
+                url
+            )));
+        }
+        return Ok(response.json().await?);
+    }
+    Err(QuomeError::Unauthorized)
+}
+
+/// Exchange a `Bearer realm="...",service="...",scope="..."` challenge for a pull token.
+async fn anonymous_token(http: &reqwest::Client, challenge: &str) -> Result<String> {
+    let params = challenge
+        .strip_prefix("Bearer ")
+        .ok_or(QuomeError::Unauthorized)?;
+
+    let mut realm = None;
+    let mut query = Vec::new();
+    for part in params.split(',') {
+        if let Some((key, value)) = part.trim().split_once('=') {
+            let value = value.trim_matches('"');
+            match key {
+                "realm" => realm = Some(value),
+                _ => query.push((key, value)),
+            }
+        }
+    }
+
+    let realm = realm.ok_or(QuomeError::Unauthorized)?;
+    let body: serde_json::Value = http.get(realm).query(&query).send().await?.json().await?;
+    body.get("token")
+        .or_else(|| body.get("access_token"))
+        .and_then(|t| t.as_str())
+        .map(String::from)
+        .ok_or(QuomeError::Unauthorized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_image_ref, ImageRef};
+
+    fn r(registry: &str, repository: &str, reference: &str) -> ImageRef {
+        ImageRef {
+            registry: registry.into(),
+            repository: repository.into(),
+            reference: reference.into(),
+        }
+    }
+
+    #[test]
+    fn parse_image_ref_defaults_and_hosts() {
+        assert_eq!(
+            parse_image_ref("nginx:1.27"),
+            r("registry-1.docker.io", "library/nginx", "1.27")
+        );
+        assert_eq!(
+            parse_image_ref("acme/api"),
+            r("registry-1.docker.io", "acme/api", "latest")
+        );
+        assert_eq!(
+            parse_image_ref("ghcr.io/acme/api:v1"),
+            r("ghcr.io", "acme/api", "v1")
+        );
+        assert_eq!(
+            parse_image_ref("localhost:5000/api"),
+            r("localhost:5000", "api", "latest")
+        );
+        assert_eq!(
+            parse_image_ref("ghcr.io/acme/api@sha256:abc"),
+            r("ghcr.io", "acme/api", "sha256:abc")
+        );
+    }
+}
@@ -1,8 +1,10 @@
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::PathBuf;
+use std::time::Duration;
 
 use crate::errors::Result;
+use crate::util::expand_path;
 
 const SETTINGS_FILE: &str = "settings.json";
 
@@ -19,6 +21,84 @@ pub struct Settings {
     /// Main website URL (e.g., "https://quome.com")
     #[serde(default = "default_website_url")]
     pub website_url: String,
+
+    /// Extra PEM CA certificate to trust (e.g., "~/certs/ca.pem")
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ca_cert_path: Option<String>,
+
+    /// PEM client certificate presented for mutual TLS
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_cert_path: Option<String>,
+
+    /// PEM private key for `client_cert_path`, if not bundled in the same file
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub client_key_path: Option<String>,
+
+    /// How failed requests are retried
+    #[serde(default, skip_serializing_if = "RetrySettings::is_default")]
+    pub retry: RetrySettings,
+
+    /// Show timestamps in the local timezone instead of UTC
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub local_time: bool,
+
+    /// strftime pattern for timestamps in tables and detail views
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp_format: Option<String>,
+
+    /// Render URLs as clickable terminal links; detected from the terminal if unset
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hyperlinks: Option<bool>,
+}
+
+/// Retry policy for transient failures: connection errors, timeouts, 429 and
+/// 502/503/504. `max_retries: 0` turns retries off.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct RetrySettings {
+    /// Extra attempts after the first one
+    pub max_retries: u32,
+    /// Wait before the first retry; doubles on each further retry
+    pub initial_backoff_ms: u64,
+    /// Upper bound for a single wait
+    pub max_backoff_ms: u64,
+    /// HTTP methods that may be retried. POST is left out by default because
+    /// repeating a create can create twice.
+    pub methods: Vec<String>,
+}
+
+impl Default for RetrySettings {
+    fn default() -> Self {
+        Self {
+            max_retries: 2,
+            initial_backoff_ms: 500,
+            max_backoff_ms: 5000,
+            methods: vec!["GET".into(), "PUT".into(), "DELETE".into()],
+        }
+    }
+}
+
+impl RetrySettings {
+    fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// How many retries a request with `method` gets.
+    pub fn retries_for(&self, method: &str) -> u32 {
+        if self.methods.iter().any(|m| m.eq_ignore_ascii_case(method)) {
+            self.max_retries
+        } else {
+            0
+        }
+    }
+
+    /// Wait before retry number `attempt` (0-based).
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let ms = self
+            .initial_backoff_ms
+            .saturating_mul(1u64.checked_shl(attempt).unwrap_or(u64::MAX));
+        Duration::from_millis(ms.min(self.max_backoff_ms))
+    }
 }
 
 fn default_api_url() -> String {
@@ -39,6 +119,13 @@ impl Default for Settings {
             api_url: default_api_url(),
             docs_url: default_docs_url(),
             website_url: default_website_url(),
+            ca_cert_path: None,
+            client_cert_path: None,
+            client_key_path: None,
+            retry: RetrySettings::default(),
+            local_time: false,
+            timestamp_format: None,
+            hyperlinks: None,
         }
     }
 }
@@ -83,8 +170,74 @@ impl Settings {
         Ok(Self::default())
     }
 
+    /// Whether an API URL has been chosen explicitly (env var or a settings file)
+    pub fn is_configured() -> bool {
+        std::env::var("QUOME_API_URL").is_ok()
+            || Self::local_settings_path().exists()
+            || Self::global_settings_path().is_ok_and(|p| p.exists())
+    }
+
+    /// Write these settings to `~/.quome/settings.json`
+    pub fn save_global(&self) -> Result<()> {
+        let path = Self::global_settings_path()?;
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(&path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+
     /// Get the API URL, with environment variable override
     pub fn get_api_url(&self) -> String {
         std::env::var("QUOME_API_URL").unwrap_or_else(|_| self.api_url.clone())
     }
+
+    /// Get the CA certificate path, with environment variable override and
+    /// `~`/`$VAR` expansion
+    pub fn get_ca_cert_path(&self) -> Option<PathBuf> {
+        std::env::var("QUOME_CA_CERT")
+            .ok()
+            .or_else(|| self.ca_cert_path.clone())
+            .map(|p| expand_path(&p))
+    }
+
+    /// Get the mTLS client certificate path, with environment variable override
+    pub fn get_client_cert_path(&self) -> Option<PathBuf> {
+        std::env::var("QUOME_CLIENT_CERT")
+            .ok()
+            .or_else(|| self.client_cert_path.clone())
+            .map(|p| expand_path(&p))
+    }
+
+    /// Get the mTLS client key path, with environment variable override
+    pub fn get_client_key_path(&self) -> Option<PathBuf> {
+        std::env::var("QUOME_CLIENT_KEY")
+            .ok()
+            .or_else(|| self.client_key_path.clone())
+            .map(|p| expand_path(&p))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RetrySettings;
+    use std::time::Duration;
+
+    #[test]
+    fn retry_backoff_doubles_up_to_the_cap() {
+        let retry = RetrySettings::default();
+        assert_eq!(retry.backoff(0), Duration::from_millis(500));
+        assert_eq!(retry.backoff(2), Duration::from_millis(2000));
+        assert_eq!(retry.backoff(4), Duration::from_millis(5000));
+        assert_eq!(retry.backoff(80), Duration::from_millis(5000));
+    }
+
+    #[test]
+    fn retry_skips_post_unless_listed() {
+        let mut retry = RetrySettings::default();
+        assert_eq!(retry.retries_for("GET"), 2);
+        assert_eq!(retry.retries_for("POST"), 0);
+        retry.methods.push("post".into());
+        assert_eq!(retry.retries_for("POST"), 2);
+    }
 }
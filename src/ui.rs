@@ -1,19 +1,168 @@
+use chrono::format::{Item, StrftimeItems};
+use chrono::{DateTime, Local, Utc};
+use clap::ValueEnum;
 use colored::Colorize;
 use indicatif::{ProgressBar, ProgressStyle};
+use serde::Serialize;
+use std::io::{IsTerminal, Write};
+use std::sync::OnceLock;
 use std::time::Duration;
 use tabled::settings::disable::Remove;
 use tabled::settings::object::Rows;
 use tabled::settings::{Alignment, Color, Modify, Panel, Style};
 use tabled::{Table, Tabled};
 
+use crate::errors::{QuomeError, Result};
+
+/// How a timestamp is laid out: table cells, detail panels, the time of day
+/// in event streams, or a bare date.
+#[derive(Clone, Copy)]
+pub enum TimeStyle {
+    List,
+    Detail,
+    Clock,
+    Date,
+}
+
+/// `--local` / `--timestamp-format`, merged with settings once at startup.
+struct TimeDisplay {
+    local: bool,
+    format: Option<String>,
+}
+
+static TIME_DISPLAY: OnceLock<TimeDisplay> = OnceLock::new();
+
+/// Choose how [`fmt_time`] renders timestamps for the rest of the process.
+/// A custom strftime `format` replaces the list and detail layouts.
+pub fn set_time_display(local: bool, format: Option<String>) -> Result<()> {
+    if let Some(ref f) = format {
+        if !is_valid_time_format(f) {
+            return Err(QuomeError::InvalidTimeFormat(f.clone()));
+        }
+    }
+    let _ = TIME_DISPLAY.set(TimeDisplay { local, format });
+    Ok(())
+}
+
+fn is_valid_time_format(format: &str) -> bool {
+    !StrftimeItems::new(format).any(|item| matches!(item, Item::Error))
+}
+
+/// Format `time` for display: UTC unless `--local` (or `local_time`) is set.
+pub fn fmt_time(time: &DateTime<Utc>, style: TimeStyle) -> String {
+    let display = TIME_DISPLAY.get();
+    let custom = display.and_then(|d| d.format.as_deref());
+    let pattern = match (style, custom) {
+        (TimeStyle::List | TimeStyle::Detail, Some(custom)) => custom,
+        (TimeStyle::List, None) => "%Y-%m-%d %H:%M",
+        (TimeStyle::Detail, None) => "%Y-%m-%d %H:%M:%S",
+        (TimeStyle::Clock, _) => "%H:%M:%S",
+        (TimeStyle::Date, _) => "%Y-%m-%d",
+    };
+    if display.is_some_and(|d| d.local) {
+        time.with_timezone(&Local).format(pattern).to_string()
+    } else {
+        time.format(pattern).to_string()
+    }
+}
+
+/// The global `-o/--output` format.
+#[derive(Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum OutputFormat {
+    #[default]
+    Table,
+    Json,
+    Yaml,
+}
+
+static OUTPUT: OnceLock<OutputFormat> = OnceLock::new();
+
+/// Choose the output format for the rest of the process.
+pub fn set_output(format: OutputFormat) {
+    let _ = OUTPUT.set(format);
+}
+
+/// Whether a command should print data rather than tables: its own `--json`
+/// flag, or `-o json|yaml`.
+pub fn structured(json: bool) -> bool {
+    json || OUTPUT.get().is_some_and(|f| *f != OutputFormat::Table)
+}
+
+/// Render `value` for [`structured`] output: YAML under `-o yaml`, pretty
+/// JSON otherwise.
+pub fn to_structured<T: Serialize + ?Sized>(value: &T) -> Result<String> {
+    Ok(match OUTPUT.get() {
+        Some(OutputFormat::Yaml) => serde_yaml::to_string(value)?.trim_end().to_string(),
+        _ => serde_json::to_string_pretty(value)?,
+    })
+}
+
+/// Print `value` with [`to_structured`].
+pub fn print_structured<T: Serialize + ?Sized>(value: &T) -> Result<()> {
+    println!("{}", to_structured(value)?);
+    Ok(())
+}
+
+/// Decide whether output is styled: not under `--no-color`, not when
+/// `NO_COLOR` is set to anything, and only when stdout is a terminal, so
+/// redirected output carries no escape codes.
+pub fn set_color(no_color: bool) {
+    let no_color_env = std::env::var_os("NO_COLOR").is_some_and(|v| !v.is_empty());
+    let enabled = !no_color && !no_color_env && std::io::stdout().is_terminal();
+    colored::control::set_override(enabled);
+}
+
+fn color() -> bool {
+    colored::control::SHOULD_COLORIZE.should_colorize()
+}
+
+static HYPERLINKS: OnceLock<bool> = OnceLock::new();
+
+/// Decide once whether [`hyperlink`] emits OSC 8 links: the `hyperlinks`
+/// setting if present, otherwise only on a terminal known to support them.
+pub fn set_hyperlinks(setting: Option<bool>) {
+    let enabled = setting.unwrap_or_else(|| {
+        std::io::stdout().is_terminal() && supports_hyperlinks(|var| std::env::var(var).ok())
+    });
+    let _ = HYPERLINKS.set(enabled);
+}
+
+/// Terminals that understand OSC 8, going by the variables they set.
+/// Anything unrecognized gets plain URLs, which are always safe.
+fn supports_hyperlinks(env: impl Fn(&str) -> Option<String>) -> bool {
+    if env("TERM").is_some_and(|t| t == "dumb") {
+        return false;
+    }
+    let program = env("TERM_PROGRAM").unwrap_or_default();
+    matches!(
+        program.as_str(),
+        "iTerm.app" | "WezTerm" | "vscode" | "ghostty" | "Hyper"
+    ) || env("VTE_VERSION")
+        .and_then(|v| v.parse::<u32>().ok())
+        .is_some_and(|v| v >= 5000)
+        || env("WT_SESSION").is_some()
+        || env("KITTY_WINDOW_ID").is_some()
+}
+
+/// `text` as a clickable link to `url` where the terminal supports it, else
+/// just `url` so nothing is lost when it's copied or piped.
+pub fn hyperlink(text: &str, url: &str) -> String {
+    if HYPERLINKS.get().copied().unwrap_or(false) {
+        format!("\x1b]8;;{}\x1b\\{}\x1b]8;;\x1b\\", url, text)
+    } else {
+        url.to_string()
+    }
+}
+
 /// Create a spinner for async operations
 pub fn spinner(message: &str) -> ProgressBar {
     let pb = ProgressBar::new_spinner();
-    pb.set_style(
-        ProgressStyle::default_spinner()
-            .template("{spinner:.cyan} {msg}")
-            .unwrap(),
-    );
+    let template = if color() {
+        "{spinner:.cyan} {msg}"
+    } else {
+        "{spinner} {msg}"
+    };
+    pb.set_style(ProgressStyle::default_spinner().template(template).unwrap());
     pb.set_message(message.to_string());
     pb.enable_steady_tick(Duration::from_millis(80));
     pb
@@ -24,10 +173,11 @@ pub fn print_table<T: Tabled>(rows: Vec<T>) {
     if rows.is_empty() {
         return;
     }
-    let table = Table::new(rows)
-        .with(Style::rounded())
-        .with(Modify::new(Rows::first()).with(Color::BOLD))
-        .to_string();
+    let mut table = Table::new(rows);
+    table.with(Style::rounded());
+    if color() {
+        table.with(Modify::new(Rows::first()).with(Color::BOLD));
+    }
     println!("{}", table);
 }
 
@@ -42,6 +192,14 @@ pub fn print_detail(title: &str, details: &[(&str, &str)]) {
     print_panel(&title.bold().to_string(), details);
 }
 
+/// `--raw`: write exactly `value` to stdout — no newline, no color — so
+/// `$(quome ...)` and `> file` capture the bytes the API returned.
+pub fn print_raw(value: &str) -> std::io::Result<()> {
+    let mut out = std::io::stdout().lock();
+    out.write_all(value.as_bytes())?;
+    out.flush()
+}
+
 fn print_panel(header: &str, details: &[(&str, &str)]) {
     if details.is_empty() {
         println!("{}", header);
@@ -80,6 +238,44 @@ pub struct AppRow {
     pub created: String,
 }
 
+/// `apps list --wide`: the default columns plus org, tier and description.
+#[derive(Tabled)]
+pub struct AppWideRow {
+    #[tabled(rename = "ID")]
+    pub id: String,
+    #[tabled(rename = "NAME")]
+    pub name: String,
+    #[tabled(rename = "STATUS")]
+    pub status: String,
+    #[tabled(rename = "URL")]
+    pub url: String,
+    #[tabled(rename = "ORG")]
+    pub org: String,
+    #[tabled(rename = "TIER")]
+    pub tier: String,
+    #[tabled(rename = "DESCRIPTION")]
+    pub description: String,
+    #[tabled(rename = "CREATED")]
+    pub created: String,
+}
+
+/// `apps list --status`: an app row plus its newest deployment's status.
+#[derive(Tabled)]
+pub struct AppStatusRow {
+    #[tabled(rename = "ID")]
+    pub id: String,
+    #[tabled(rename = "NAME")]
+    pub name: String,
+    #[tabled(rename = "STATUS")]
+    pub status: String,
+    #[tabled(rename = "DEPLOYMENT")]
+    pub deployment: String,
+    #[tabled(rename = "URL")]
+    pub url: String,
+    #[tabled(rename = "CREATED")]
+    pub created: String,
+}
+
 #[derive(Tabled)]
 pub struct OrgRow {
     #[tabled(rename = "ID")]
@@ -88,6 +284,8 @@ pub struct OrgRow {
     pub name: String,
     #[tabled(rename = "SLUG")]
     pub slug: String,
+    #[tabled(rename = "ROLE")]
+    pub role: String,
     #[tabled(rename = "CREATED")]
     pub created: String,
 }
@@ -138,6 +336,26 @@ pub struct MemberRow {
     pub joined: String,
 }
 
+#[derive(Tabled)]
+pub struct ContainerRow {
+    #[tabled(rename = "NAME")]
+    pub name: String,
+    #[tabled(rename = "IMAGE")]
+    pub image: String,
+    #[tabled(rename = "PORT")]
+    pub port: String,
+}
+
+#[derive(Tabled)]
+pub struct EnvRow {
+    #[tabled(rename = "NAME")]
+    pub name: String,
+    #[tabled(rename = "VALUE")]
+    pub value: String,
+    #[tabled(rename = "SOURCE")]
+    pub source: String,
+}
+
 #[derive(Tabled)]
 pub struct EventRow {
     #[tabled(rename = "TIME")]
@@ -163,3 +381,85 @@ pub struct DatabaseRow {
     #[tabled(rename = "CREATED")]
     pub created: String,
 }
+
+/// `databases list --wide`: the default columns plus storage, HA and address.
+#[derive(Tabled)]
+pub struct DatabaseWideRow {
+    #[tabled(rename = "ID")]
+    pub id: String,
+    #[tabled(rename = "NAME")]
+    pub name: String,
+    #[tabled(rename = "VERSION")]
+    pub version: String,
+    #[tabled(rename = "TIER")]
+    pub tier: String,
+    #[tabled(rename = "STORAGE")]
+    pub storage: String,
+    #[tabled(rename = "HA")]
+    pub ha: String,
+    #[tabled(rename = "PRIVATE IP")]
+    pub private_ip: String,
+    #[tabled(rename = "STATUS")]
+    pub status: String,
+    #[tabled(rename = "CREATED")]
+    pub created: String,
+}
+
+#[derive(Tabled)]
+pub struct LevelCountRow {
+    #[tabled(rename = "LEVEL")]
+    pub level: String,
+    #[tabled(rename = "COUNT")]
+    pub count: usize,
+    #[tabled(rename = "SHARE")]
+    pub share: String,
+}
+
+#[derive(Tabled)]
+pub struct MessageCountRow {
+    #[tabled(rename = "COUNT")]
+    pub count: usize,
+    #[tabled(rename = "MESSAGE")]
+    pub message: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{fmt_time, is_valid_time_format, supports_hyperlinks, TimeStyle};
+    use chrono::{TimeZone, Utc};
+
+    #[test]
+    fn fmt_time_defaults_to_utc_layouts() {
+        let t = Utc.with_ymd_and_hms(2026, 7, 2, 7, 14, 2).unwrap();
+        assert_eq!(fmt_time(&t, TimeStyle::List), "2026-07-02 07:14");
+        assert_eq!(fmt_time(&t, TimeStyle::Detail), "2026-07-02 07:14:02");
+        assert_eq!(fmt_time(&t, TimeStyle::Clock), "07:14:02");
+        assert_eq!(fmt_time(&t, TimeStyle::Date), "2026-07-02");
+    }
+
+    #[test]
+    fn rejects_bad_strftime_patterns() {
+        assert!(is_valid_time_format("%d/%m/%Y %H:%M %Z"));
+        assert!(!is_valid_time_format("%Y-%Q"));
+    }
+
+    #[test]
+    fn hyperlink_support_is_detected_from_known_terminals() {
+        let env = |pairs: &'static [(&'static str, &'static str)]| {
+            move |var: &str| {
+                pairs
+                    .iter()
+                    .find(|(k, _)| *k == var)
+                    .map(|(_, v)| v.to_string())
+            }
+        };
+        assert!(supports_hyperlinks(env(&[("TERM_PROGRAM", "iTerm.app")])));
+        assert!(supports_hyperlinks(env(&[("VTE_VERSION", "7006")])));
+        assert!(!supports_hyperlinks(env(&[("VTE_VERSION", "4601")])));
+        assert!(!supports_hyperlinks(env(&[("TERM", "xterm-256color")])));
+        assert!(!supports_hyperlinks(env(&[
+            ("TERM", "dumb"),
+            ("WT_SESSION", "1")
+        ])));
+    }
+}
@@ -0,0 +1,411 @@
+use chrono::{DateTime, NaiveDate, Utc};
+use std::fs;
+use std::future::Future;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use crate::errors::{QuomeError, Result};
+
+/// Credentials and secret values seen by this process, masked by [`redact`].
+static SENSITIVE: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// JSON fields whose values are never shown in debug or error output.
+/// `input` is where the API echoes rejected request values back.
+const SENSITIVE_FIELDS: &[&str] = &[
+    "access_token",
+    "api_key",
+    "authorization",
+    "input",
+    "key",
+    "password",
+    "refresh_token",
+    "secret",
+    "token",
+    "value",
+];
+
+const MASK: &str = "****";
+
+/// How often state-polling commands (`--wait` and friends) re-fetch.
+pub const POLL_INTERVAL: Duration = Duration::from_secs(5);
+
+/// Expand a leading `~` and any `$VAR` / `${VAR}` references in a path taken
+/// from a config file. Unset variables are left verbatim so the eventual
+/// "file not found" error shows what was missing.
+pub fn expand_path(raw: &str) -> PathBuf {
+    let mut expanded = String::with_capacity(raw.len());
+
+    let rest = match raw.strip_prefix('~') {
+        Some(rest) if rest.is_empty() || rest.starts_with('/') => match dirs::home_dir() {
+            Some(home) => {
+                expanded.push_str(&home.to_string_lossy());
+                rest
+            }
+            None => raw,
+        },
+        _ => raw,
+    };
+
+    let mut chars = rest.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c != '$' {
+            expanded.push(c);
+            continue;
+        }
+
+        let braced = chars.peek() == Some(&'{');
+        if braced {
+            chars.next();
+        }
+
+        let mut name = String::new();
+        while let Some(&n) = chars.peek() {
+            if n.is_ascii_alphanumeric() || n == '_' {
+                name.push(n);
+                chars.next();
+            } else {
+                break;
+            }
+        }
+        let closed = braced && chars.peek() == Some(&'}');
+        if closed {
+            chars.next();
+        }
+
+        match std::env::var(&name) {
+            Ok(value) if !name.is_empty() && braced == closed => expanded.push_str(&value),
+            _ => {
+                expanded.push('$');
+                if braced {
+                    expanded.push('{');
+                }
+                expanded.push_str(&name);
+                if closed {
+                    expanded.push('}');
+                }
+            }
+        }
+    }
+
+    PathBuf::from(expanded)
+}
+
+/// Parse a `--since`/`--until` bound: an RFC 3339 timestamp, a bare
+/// `YYYY-MM-DD` meaning midnight UTC at the start of that day, or a duration
+/// ago such as `15m`, `2h` or `1d`.
+pub fn parse_date(raw: &str) -> Result<DateTime<Utc>> {
+    parse_date_at(raw, Utc::now())
+}
+
+fn parse_date_at(raw: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    if let Ok(ts) = DateTime::parse_from_rfc3339(raw) {
+        return Ok(ts.with_timezone(&Utc));
+    }
+    if let Some(ago) = parse_duration(raw) {
+        return now
+            .checked_sub_signed(ago)
+            .ok_or_else(|| QuomeError::InvalidDate(raw.to_string()));
+    }
+    NaiveDate::parse_from_str(raw, "%Y-%m-%d")
+        .ok()
+        .and_then(|d| d.and_hms_opt(0, 0, 0))
+        .map(|dt| dt.and_utc())
+        .ok_or_else(|| QuomeError::InvalidDate(raw.to_string()))
+}
+
+/// `<number><unit>` with unit `s`, `m`, `h`, `d` or `w`.
+fn parse_duration(raw: &str) -> Option<chrono::Duration> {
+    let unit = raw.chars().last()?;
+    let amount: i64 = raw[..raw.len() - unit.len_utf8()].parse().ok()?;
+    let seconds = match unit {
+        's' => 1,
+        'm' => 60,
+        'h' => 60 * 60,
+        'd' => 24 * 60 * 60,
+        'w' => 7 * 24 * 60 * 60,
+        _ => return None,
+    };
+    amount
+        .checked_mul(seconds)
+        .filter(|s| *s >= 0)
+        .and_then(chrono::Duration::try_seconds)
+}
+
+/// Re-run `fetch` every `interval` until `done` accepts the result, giving
+/// up after `timeout` (`Duration::MAX` waits forever). `done` sees every
+/// intermediate value, so callers can report state transitions from it.
+///
+/// The timeout bounds the whole loop: a request still in flight at the
+/// deadline is abandoned rather than allowed to run out the client timeout.
+pub async fn poll_until<T, F, Fut>(
+    interval: Duration,
+    timeout: Duration,
+    mut fetch: F,
+    mut done: impl FnMut(&T) -> bool,
+) -> Result<T>
+where
+    F: FnMut() -> Fut,
+    Fut: Future<Output = Result<T>>,
+{
+    let deadline = Instant::now().checked_add(timeout);
+    let timed_out = || QuomeError::Timeout(timeout.as_secs());
+    loop {
+        let value = match deadline {
+            Some(d) => tokio::time::timeout(d.saturating_duration_since(Instant::now()), fetch())
+                .await
+                .map_err(|_| timed_out())??,
+            None => fetch().await?,
+        };
+        if done(&value) {
+            return Ok(value);
+        }
+        if deadline.is_some_and(|d| Instant::now() + interval > d) {
+            return Err(timed_out());
+        }
+        tokio::time::sleep(interval).await;
+    }
+}
+
+/// Remember a token or secret value so [`redact`] masks it wherever it shows
+/// up. Very short values are ignored; masking every "1" would garble output.
+pub fn register_sensitive(value: &str) {
+    if value.len() < 6 {
+        return;
+    }
+    let mut known = SENSITIVE.lock().unwrap_or_else(|e| e.into_inner());
+    if !known.iter().any(|v| v == value) {
+        known.push(value.to_string());
+    }
+}
+
+/// Mask credentials in text bound for stderr (`QUOME_DEBUG`, error messages):
+/// secret-looking JSON fields, `Bearer` tokens and every registered value.
+pub fn redact(text: &str) -> String {
+    let mut out = match serde_json::from_str::<serde_json::Value>(text) {
+        Ok(mut value) if value.is_object() || value.is_array() => {
+            mask_fields(&mut value);
+            value.to_string()
+        }
+        _ => text.to_string(),
+    };
+
+    let mut rest = out.as_str();
+    let mut masked = String::with_capacity(out.len());
+    while let Some(i) = rest.find("Bearer ") {
+        let (head, tail) = rest.split_at(i + "Bearer ".len());
+        let end = tail
+            .find(|c: char| c.is_whitespace() || c == '"' || c == '\'')
+            .unwrap_or(tail.len());
+        masked.push_str(head);
+        masked.push_str(MASK);
+        rest = &tail[end..];
+    }
+    masked.push_str(rest);
+    out = masked;
+
+    let mut known = SENSITIVE.lock().unwrap_or_else(|e| e.into_inner()).clone();
+    // Longest first, so a value containing another is masked whole
+    known.sort_by_key(|v| std::cmp::Reverse(v.len()));
+    for value in &known {
+        out = out.replace(value.as_str(), MASK);
+    }
+    out
+}
+
+fn mask_fields(value: &mut serde_json::Value) {
+    match value {
+        serde_json::Value::Object(map) => {
+            for (k, v) in map.iter_mut() {
+                if !v.is_null() && SENSITIVE_FIELDS.contains(&k.to_ascii_lowercase().as_str()) {
+                    *v = serde_json::Value::String(MASK.into());
+                } else {
+                    mask_fields(v);
+                }
+            }
+        }
+        serde_json::Value::Array(items) => items.iter_mut().for_each(mask_fields),
+        _ => {}
+    }
+}
+
+/// Write `contents` to `path`, readable only by the current user. With
+/// `overwrite` an existing file is truncated and narrowed to 0600 too;
+/// without it the file must not exist yet, which the open itself checks so
+/// nothing (not even a symlink) can appear at `path` in between.
+pub fn write_private(path: &Path, contents: &[u8], overwrite: bool) -> Result<()> {
+    use std::io::Write;
+
+    let mut options = fs::OpenOptions::new();
+    options.write(true);
+    if overwrite {
+        options.create(true).truncate(true);
+    } else {
+        options.create_new(true);
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+        options.mode(0o600);
+        let mut file = options.open(path)?;
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+        file.write_all(contents)?;
+    }
+    #[cfg(not(unix))]
+    options.open(path)?.write_all(contents)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{expand_path, parse_date, parse_date_at, poll_until, redact, register_sensitive};
+    use crate::errors::QuomeError;
+    use std::cell::Cell;
+    use std::path::PathBuf;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn expands_tilde() {
+        let home = dirs::home_dir().unwrap();
+        assert_eq!(expand_path("~"), home);
+        assert_eq!(expand_path("~/certs/ca.pem"), home.join("certs/ca.pem"));
+        // `~user` forms are not supported and pass through untouched
+        assert_eq!(expand_path("~bob/x"), PathBuf::from("~bob/x"));
+    }
+
+    #[test]
+    fn expands_env_vars() {
+        let home = std::env::var("HOME").unwrap();
+        assert_eq!(
+            expand_path("$HOME/certs/ca.pem"),
+            PathBuf::from(&home).join("certs/ca.pem")
+        );
+        assert_eq!(
+            expand_path("${HOME}/ca.pem"),
+            PathBuf::from(&home).join("ca.pem")
+        );
+        assert_eq!(
+            expand_path("/x/$QUOME_TEST_SURELY_UNSET/y"),
+            PathBuf::from("/x/$QUOME_TEST_SURELY_UNSET/y")
+        );
+    }
+
+    #[test]
+    fn leaves_absolute_paths_alone() {
+        assert_eq!(
+            expand_path("/etc/ssl/ca.pem"),
+            PathBuf::from("/etc/ssl/ca.pem")
+        );
+        assert_eq!(
+            expand_path("relative/ca.pem"),
+            PathBuf::from("relative/ca.pem")
+        );
+    }
+
+    #[test]
+    fn parses_dates_and_timestamps() {
+        assert_eq!(
+            parse_date("2026-07-01").unwrap().to_rfc3339(),
+            "2026-07-01T00:00:00+00:00"
+        );
+        assert_eq!(
+            parse_date("2026-07-01T09:30:00+02:00")
+                .unwrap()
+                .to_rfc3339(),
+            "2026-07-01T07:30:00+00:00"
+        );
+        assert!(parse_date("July 1st").is_err());
+        assert!(parse_date("2026-13-01").is_err());
+    }
+
+    #[test]
+    fn parses_relative_durations() {
+        let now = parse_date("2026-07-01T12:00:00Z").unwrap();
+        let ago = |raw: &str| parse_date_at(raw, now).map(|t| t.to_rfc3339());
+        assert_eq!(ago("15m").unwrap(), "2026-07-01T11:45:00+00:00");
+        assert_eq!(ago("2h").unwrap(), "2026-07-01T10:00:00+00:00");
+        assert_eq!(ago("1d").unwrap(), "2026-06-30T12:00:00+00:00");
+        assert_eq!(ago("1w").unwrap(), "2026-06-24T12:00:00+00:00");
+        assert_eq!(ago("30s").unwrap(), "2026-07-01T11:59:30+00:00");
+        for bad in ["15", "m", "-5m", "2x", "1.5h", "99999999999999999w"] {
+            assert!(ago(bad).is_err(), "{}", bad);
+        }
+    }
+
+    #[tokio::test]
+    async fn poll_until_sees_every_state_until_done() {
+        let states = ["pending", "provisioning", "running", "unreachable"];
+        let calls = Cell::new(0);
+        let mut seen = Vec::new();
+
+        let last = poll_until(
+            Duration::from_millis(1),
+            Duration::MAX,
+            || {
+                let state = states[calls.get()];
+                calls.set(calls.get() + 1);
+                async move { Ok(state) }
+            },
+            |state| {
+                seen.push(*state);
+                *state == "running"
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(last, "running");
+        assert_eq!(seen, ["pending", "provisioning", "running"]);
+    }
+
+    #[tokio::test]
+    async fn poll_until_gives_up_at_the_deadline() {
+        let result = poll_until(
+            Duration::from_millis(20),
+            Duration::from_millis(50),
+            || async { Ok("pending") },
+            |_| false,
+        )
+        .await;
+        assert!(matches!(result, Err(QuomeError::Timeout(_))));
+    }
+
+    #[tokio::test]
+    async fn poll_until_cuts_a_slow_request_short() {
+        let started = Instant::now();
+        let result = poll_until(
+            Duration::from_millis(1),
+            Duration::from_millis(50),
+            || async {
+                tokio::time::sleep(Duration::from_secs(30)).await;
+                Ok("pending")
+            },
+            |_| false,
+        )
+        .await;
+        assert!(matches!(result, Err(QuomeError::Timeout(_))));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+
+    #[test]
+    fn redact_masks_fields_bearer_tokens_and_registered_values() {
+        register_sensitive("qk_redact_test_0123456789");
+
+        let body =
+            r#"{"id":1,"name":"DB_URL","value":"postgres://u:p@h/db","nested":[{"key":"k-123"}]}"#;
+        let out = redact(body);
+        assert!(!out.contains("postgres://"), "{}", out);
+        assert!(!out.contains("k-123"), "{}", out);
+        assert!(out.contains(r#""name":"DB_URL""#), "{}", out);
+
+        let out = redact("401 for Authorization: Bearer eyJhbGciOi.abc rejected");
+        assert_eq!(out, "401 for Authorization: Bearer **** rejected");
+
+        let out = redact("token qk_redact_test_0123456789 is invalid");
+        assert_eq!(out, "token **** is invalid");
+
+        // Too short to register safely
+        register_sensitive("1");
+        assert_eq!(redact("retry 1 of 3"), "retry 1 of 3");
+    }
+}
@@ -0,0 +1,136 @@
+//! End-to-end check that the client certificate is presented during the TLS
+//! handshake. Needs the `openssl` binary, so it only builds with
+//! `cargo test --features mtls-tests`.
+#![cfg(feature = "mtls-tests")]
+
+use std::net::{TcpListener, TcpStream};
+use std::path::{Path, PathBuf};
+use std::process::{Child, Command, Output, Stdio};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+struct Server {
+    child: Child,
+    port: u16,
+}
+
+impl Drop for Server {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+    }
+}
+
+/// Run `openssl` with whitespace-separated `args` inside `dir`.
+fn openssl(dir: &Path, args: &str) {
+    let status = Command::new("openssl")
+        .args(args.split_whitespace())
+        .current_dir(dir)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .status()
+        .expect("openssl must be installed for mtls-tests");
+    assert!(status.success(), "openssl {} failed", args);
+}
+
+/// A throwaway CA plus a server and a client certificate signed by it.
+fn make_pki() -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("quome-mtls-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+
+    openssl(
+        &dir,
+        "req -x509 -newkey rsa:2048 -nodes -days 1 -keyout ca.key -out ca.pem \
+         -subj /CN=quome-test-ca -addext basicConstraints=critical,CA:TRUE \
+         -addext keyUsage=critical,keyCertSign,cRLSign",
+    );
+    for (name, ext) in [
+        (
+            "server",
+            "subjectAltName=DNS:localhost\nextendedKeyUsage=serverAuth\n",
+        ),
+        ("client", "extendedKeyUsage=clientAuth\n"),
+    ] {
+        std::fs::write(dir.join(format!("{name}.ext")), ext).unwrap();
+        openssl(
+            &dir,
+            &format!(
+                "req -newkey rsa:2048 -nodes -keyout {name}.key -out {name}.csr -subj /CN={name}"
+            ),
+        );
+        openssl(
+            &dir,
+            &format!(
+                "x509 -req -days 1 -CA ca.pem -CAkey ca.key -CAcreateserial \
+                 -in {name}.csr -out {name}.pem -extfile {name}.ext"
+            ),
+        );
+    }
+    dir
+}
+
+/// `openssl s_server` that rejects any handshake without a valid client cert.
+fn start_server(pki: &Path) -> Server {
+    let port = TcpListener::bind("127.0.0.1:0")
+        .unwrap()
+        .local_addr()
+        .unwrap()
+        .port();
+
+    let args = format!(
+        "s_server -www -Verify 1 -verify_return_error -cert server.pem -key server.key \
+         -CAfile ca.pem -accept {port}"
+    );
+    let child = Command::new("openssl")
+        .args(args.split_whitespace())
+        .current_dir(pki)
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .unwrap();
+
+    let deadline = Instant::now() + Duration::from_secs(10);
+    while TcpStream::connect(("127.0.0.1", port)).is_err() {
+        assert!(Instant::now() < deadline, "s_server did not start");
+        sleep(Duration::from_millis(100));
+    }
+    Server { child, port }
+}
+
+fn whoami(server: &Server, pki: &Path, identity: Option<(&str, &str)>) -> Output {
+    let home = pki.join("home");
+    let mut cmd = Command::new(env!("CARGO_BIN_EXE_quome"));
+    cmd.arg("whoami")
+        .env_clear()
+        .env("HOME", &home)
+        .env("QUOME_TOKEN", "qk_test")
+        .env(
+            "QUOME_API_URL",
+            format!("https://localhost:{}", server.port),
+        )
+        .env("QUOME_CA_CERT", pki.join("ca.pem"));
+    if let Some((cert, key)) = identity {
+        cmd.env("QUOME_CLIENT_CERT", pki.join(cert))
+            .env("QUOME_CLIENT_KEY", pki.join(key));
+    }
+    cmd.output().unwrap()
+}
+
+#[test]
+fn client_certificate_is_presented() {
+    let pki = make_pki();
+    let server = start_server(&pki);
+
+    // No identity: the server aborts the handshake, so the request never completes
+    let rejected = whoami(&server, &pki, None);
+    let stderr = String::from_utf8_lossy(&rejected.stderr);
+    assert!(stderr.contains("error sending request"), "{}", stderr);
+
+    // With the identity the handshake succeeds; s_server answers with an HTML
+    // status page, which fails later as a non-JSON body rather than a TLS error
+    let accepted = whoami(&server, &pki, Some(("client.pem", "client.key")));
+    let stderr = String::from_utf8_lossy(&accepted.stderr);
+    assert!(!stderr.contains("error sending request"), "{}", stderr);
+
+    drop(server);
+    let _ = std::fs::remove_dir_all(&pki);
+}